@@ -1,2 +1,6 @@
 pub mod keys;
 pub mod msg;
+#[cfg(feature = "mt")]
+pub mod multitest;
+pub mod token_factory;
+pub mod vote_token;
@@ -1,16 +1,99 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Coin, CosmosMsg, Uint128};
 
 pub mod membership {
     use cosmwasm_schema::QueryResponses;
+    use cosmwasm_std::{Addr, Decimal, Timestamp};
+    use cw2::ContractVersion;
+    use cw20::Cw20ReceiveMsg;
+    use cw_utils::Duration;
 
     use super::*;
 
     #[cw_serde]
     pub enum ExecMsg {
-        ProposeMember { addr: String },
-        VoteMemberProposal { voter: String, voter_proxy: String },
+        ProposeMember {
+            addr: String,
+        },
+        ProposeGeneric {
+            title: String,
+            description: String,
+            msgs: Vec<CosmosMsg>,
+        },
+        ProposeRemoval {
+            owner: String,
+        },
+        VoteMemberProposal {
+            voter_proxy: String,
+            amount: Uint128,
+        },
+        VoteGenericProposal {
+            voter_proxy: String,
+            amount: Uint128,
+        },
+        VoteRemovalProposal {
+            voter_proxy: String,
+            amount: Uint128,
+        },
+        /// cw20 vote tokens sent along with any of the messages
+        Receive(Cw20ReceiveMsg),
         NewMember {},
+        /// pool of a reserve held vote token which was not paid on instantiation, split among
+        /// the initial members
+        FundPool {},
+        MemberLeft {},
+        /// vote tokens sent along are delegated to the member `to`, whose proxy holds and votes
+        /// them
+        Delegate {
+            to: String,
+        },
+        /// takes `amount` delegated to the member `from` back, as far as its proxy still holds
+        /// vote tokens
+        Undelegate {
+            from: String,
+            amount: Uint128,
+        },
+        /// vote tokens a voter took back from an open proposal, refunded to `voter_proxy`
+        VoteRetracted {
+            voter_proxy: String,
+            amount: Uint128,
+            /// retracted from a yes vote
+            yes: bool,
+        },
+        /// escrow refunded to the voter proxies of a closed proposal
+        ProposalClosed {
+            refunds: Vec<VoterTokens>,
+        },
+        MigrateChildren {
+            code_id: u64,
+            kind: ChildKind,
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+        UpdateConfig {
+            config: ConfigUpdate,
+        },
+    }
+
+    #[cw_serde]
+    #[derive(Default)]
+    pub struct ConfigUpdate {
+        pub joining_fee: Option<Vec<Coin>>,
+        pub vote_token_price: Option<Coin>,
+        pub initial_votes: Option<Uint128>,
+        pub initial_vote_token_distribution_part: Option<Decimal>,
+        pub voting_period: Option<Duration>,
+        pub quorum: Option<Decimal>,
+        pub threshold: Option<Decimal>,
+        pub proxy_code_id: Option<u64>,
+        pub proposal_code_id: Option<u64>,
+    }
+
+    #[cw_serde]
+    pub enum ChildKind {
+        Proxy,
+        Proposal,
+        Distribution,
     }
 
     #[cw_serde]
@@ -20,8 +103,53 @@ pub mod membership {
         IsMember { addr: String },
         #[returns(IsMemberResp)]
         IsProposedMember { addr: String },
+        /// Whether `addr` is a proposal instantiated by membership
+        #[returns(IsProposalResp)]
+        IsProposal { addr: String },
         #[returns(OwnerProxyResp)]
         OwnerProxy { owner: String },
+        #[returns(OwnerProxyResp)]
+        MemberByProxy { proxy: String },
+        #[returns(MemberProfileResp)]
+        MemberProfile { owner: String },
+        #[returns(ListMembersResp)]
+        ListMembers {
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+        #[returns(ListCandidatesResp)]
+        ListCandidates {
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+        #[returns(MemberCountResp)]
+        MemberCount {},
+        /// Compares the supply recorded by distribution with the vote token balances of all
+        /// member proxies and proposals. Walks every member and proposal, so it is meant
+        /// for off-chain monitoring.
+        #[returns(SupplyInvariantResp)]
+        SupplyInvariant {},
+        /// Vote tokens the member `address` holds outside of proposals at the beginning of
+        /// `height`, the current one when omitted. Together with `TotalPowerAtHeight`, `Info`
+        /// and `Dao` it is the DAO voting module interface.
+        #[returns(VotingPowerAtHeightResp)]
+        VotingPowerAtHeight {
+            address: String,
+            height: Option<u64>,
+        },
+        /// Vote tokens in circulation at the beginning of `height`, the current one when omitted.
+        /// It includes vote tokens escrowed in proposals, which no member `VotingPowerAtHeight`
+        /// counts, so member powers only add up to it when no escrow is open.
+        #[returns(TotalPowerAtHeightResp)]
+        TotalPowerAtHeight { height: Option<u64> },
+        #[returns(InfoResp)]
+        Info {},
+        /// Membership executes passed proposals itself, so it is its own DAO
+        #[returns(Addr)]
+        Dao {},
+        /// Vote tokens the member `owner` delegated to other members and those delegated to it
+        #[returns(DelegationsResp)]
+        Delegations { owner: String },
     }
 
     #[cw_serde]
@@ -34,11 +162,102 @@ pub mod membership {
         pub ok: bool,
     }
 
+    #[cw_serde]
+    pub struct IsProposalResp {
+        pub ok: bool,
+    }
+
     #[cw_serde]
     pub struct OwnerProxyResp {
         pub owner: String,
         pub proxy: String,
     }
+
+    #[cw_serde]
+    pub struct MemberProfileResp {
+        pub owner: String,
+        pub proxy: String,
+        pub joined_height: u64,
+        pub joined_time: Timestamp,
+        pub proposal: Option<String>,
+        pub initial_votes: Coin,
+        pub withdrawable: Vec<Coin>,
+    }
+
+    #[cw_serde]
+    pub struct MemberResp {
+        pub owner: String,
+        pub proxy: String,
+        pub vote_tokens: Coin,
+    }
+
+    #[cw_serde]
+    pub struct ListMembersResp {
+        pub members: Vec<MemberResp>,
+    }
+
+    #[cw_serde]
+    pub struct CandidateResp {
+        pub candidate: String,
+        pub proposal: String,
+    }
+
+    #[cw_serde]
+    pub struct ListCandidatesResp {
+        pub candidates: Vec<CandidateResp>,
+    }
+
+    #[cw_serde]
+    pub struct MemberCountResp {
+        pub count: u64,
+    }
+
+    #[cw_serde]
+    pub struct SupplyInvariantResp {
+        pub recorded_circulating: Coin,
+        pub actual_circulating: Coin,
+        pub recorded_locked: Coin,
+        pub actual_locked: Coin,
+        pub holds: bool,
+    }
+
+    #[cw_serde]
+    pub struct VotingPowerAtHeightResp {
+        pub power: Uint128,
+        pub height: u64,
+    }
+
+    #[cw_serde]
+    pub struct TotalPowerAtHeightResp {
+        pub power: Uint128,
+        pub height: u64,
+    }
+
+    #[cw_serde]
+    pub struct InfoResp {
+        pub info: ContractVersion,
+    }
+
+    #[cw_serde]
+    pub struct DelegationResp {
+        pub owner: String,
+        pub proxy: String,
+        pub amount: Uint128,
+    }
+
+    #[cw_serde]
+    pub struct DelegationsResp {
+        /// members holding vote tokens of the member
+        pub delegated: Vec<DelegationResp>,
+        /// members whose vote tokens the member holds
+        pub received: Vec<DelegationResp>,
+    }
+}
+
+#[cw_serde]
+pub struct VoterTokens {
+    pub voter: String,
+    pub amount: Uint128,
 }
 
 #[cw_serde]
@@ -47,14 +266,26 @@ pub struct ProposalMemberData {
     pub proposal_addr: String,
 }
 
+#[cw_serde]
+pub struct ProposalGenericData {
+    pub proposal_addr: String,
+}
+
 #[cw_serde]
 pub struct ProxyMemberData {
     pub owner_addr: String,
     pub proxy_addr: String,
 }
 
+#[cw_serde]
+pub struct BuyVoteTokensResp {
+    pub vote_tokens: Coin,
+    pub change: Coin,
+}
+
 #[cw_serde]
 #[derive(Default)]
 pub struct WithdrawableResp {
-    pub funds: Option<Coin>,
+    /// every reward denom with something to withdraw
+    pub funds: Vec<Coin>,
 }
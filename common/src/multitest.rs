@@ -0,0 +1,145 @@
+use crate::token_factory::{factory_denom, TokenFactoryMsg};
+use anyhow::{bail, ensure, Result as AnyResult};
+use cosmwasm_std::{
+    coins, testing::MockApi, Addr, Api, BankMsg, Binary, BlockInfo, Coin, CustomQuery, Empty,
+    MemoryStorage, Querier, Storage,
+};
+use cw_multi_test::{
+    App as MultiTestApp, AppResponse, BankKeeper, BankSudo, BasicAppBuilder, CosmosRouter, Module,
+    WasmKeeper,
+};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+/// Multitest app of all contracts, distribution built with the `token-factory` feature sends
+/// `TokenFactoryMsg`s, other contracts are stored with empty custom messages
+pub type App = MultiTestApp<
+    BankKeeper,
+    MockApi,
+    MemoryStorage,
+    TokenFactory,
+    WasmKeeper<TokenFactoryMsg, Empty>,
+>;
+
+// denom => creator
+const DENOMS: Map<&str, Addr> = Map::new("token_factory_denoms");
+
+/// Stands in for the chain token factory module, creators mint and burn their own denoms
+pub struct TokenFactory;
+
+impl TokenFactory {
+    pub fn app() -> App {
+        Self::app_with_balances([])
+    }
+
+    #[track_caller]
+    pub fn app_with_balances(balances: impl IntoIterator<Item = (Addr, Vec<Coin>)>) -> App {
+        BasicAppBuilder::<TokenFactoryMsg, Empty>::new_custom()
+            .with_custom(TokenFactory)
+            .build(|router, _, storage| {
+                for (addr, funds) in balances {
+                    router.bank.init_balance(storage, &addr, funds).unwrap();
+                }
+            })
+    }
+
+    fn ensure_creator(storage: &dyn Storage, denom: &str, sender: &Addr) -> AnyResult<()> {
+        let creator = DENOMS.may_load(storage, denom)?;
+        ensure!(
+            creator.as_ref() == Some(sender),
+            "{} is not the creator of {}",
+            sender,
+            denom
+        );
+        Ok(())
+    }
+}
+
+impl Module for TokenFactory {
+    type ExecT = TokenFactoryMsg;
+    type QueryT = Empty;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: TokenFactoryMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match msg {
+            TokenFactoryMsg::CreateDenom { subdenom } => {
+                let denom = factory_denom(&sender, &subdenom);
+                ensure!(
+                    !DENOMS.has(storage, &denom),
+                    "denom {} already exists",
+                    denom
+                );
+                DENOMS.save(storage, &denom, &sender)?;
+                Ok(AppResponse::default())
+            }
+            TokenFactoryMsg::Mint {
+                denom,
+                amount,
+                mint_to_address,
+            } => {
+                Self::ensure_creator(storage, &denom, &sender)?;
+                let mint = BankSudo::Mint {
+                    to_address: mint_to_address,
+                    amount: coins(amount.u128(), denom),
+                };
+                router.sudo(api, storage, block, mint.into())
+            }
+            TokenFactoryMsg::Burn {
+                denom,
+                amount,
+                burn_from_address,
+            } => {
+                Self::ensure_creator(storage, &denom, &sender)?;
+                ensure!(
+                    burn_from_address == sender.as_str(),
+                    "only own {} can be burned",
+                    denom
+                );
+                let burn = BankMsg::Burn {
+                    amount: coins(amount.u128(), denom),
+                };
+                router.execute(api, storage, block, sender, burn.into())
+            }
+        }
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("Unexpected token factory sudo {:?}", msg)
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: Empty,
+    ) -> AnyResult<Binary> {
+        bail!("Unexpected token factory query {:?}", request)
+    }
+}
@@ -0,0 +1,28 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, CustomMsg, Uint128};
+
+pub const VOTE_SUBDENOM: &str = "vote";
+
+/// Messages of the chain token factory module, denoms are owned by the contract creating them
+#[cw_serde]
+pub enum TokenFactoryMsg {
+    CreateDenom {
+        subdenom: String,
+    },
+    Mint {
+        denom: String,
+        amount: Uint128,
+        mint_to_address: String,
+    },
+    Burn {
+        denom: String,
+        amount: Uint128,
+        burn_from_address: String,
+    },
+}
+
+impl CustomMsg for TokenFactoryMsg {}
+
+pub fn factory_denom(creator: &Addr, subdenom: &str) -> String {
+    format!("factory/{}/{}", creator, subdenom)
+}
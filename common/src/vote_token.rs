@@ -0,0 +1,138 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, MessageInfo, QuerierWrapper,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use serde::Serialize;
+
+/// Vote token chosen on membership instantiation
+#[cw_serde]
+pub enum VoteToken {
+    /// bank denom, either the `VOTE` pool paid on instantiation or a token factory denom
+    Native {},
+    Cw20(Cw20Source),
+}
+
+#[cw_serde]
+pub enum Cw20Source {
+    /// new cw20 token minted by distribution
+    CodeId(u64),
+    /// existing cw20 token, distribution keeps a reserve of it
+    Addr(String),
+}
+
+/// Vote token as moved around by the contracts
+#[cw_serde]
+pub enum VoteAsset {
+    Native(String),
+    Cw20(Addr),
+}
+
+impl VoteAsset {
+    /// Denom vote tokens are accounted in, cw20 tokens received with `Receive` are paid in it
+    pub fn denom(&self) -> String {
+        match self {
+            VoteAsset::Native(denom) => denom.clone(),
+            VoteAsset::Cw20(token) => format!("cw20:{}", token),
+        }
+    }
+
+    pub fn coin(&self, amount: Uint128) -> Coin {
+        Coin {
+            denom: self.denom(),
+            amount,
+        }
+    }
+
+    pub fn balance(&self, querier: &QuerierWrapper, addr: impl Into<String>) -> StdResult<Uint128> {
+        match self {
+            VoteAsset::Native(denom) => Ok(querier.query_balance(addr, denom)?.amount),
+            VoteAsset::Cw20(token) => {
+                let resp: BalanceResponse = querier.query_wasm_smart(
+                    token,
+                    &Cw20QueryMsg::Balance {
+                        address: addr.into(),
+                    },
+                )?;
+                Ok(resp.balance)
+            }
+        }
+    }
+
+    pub fn transfer<T>(
+        &self,
+        recipient: impl Into<String>,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = match self {
+            VoteAsset::Native(denom) => BankMsg::Send {
+                to_address: recipient.into(),
+                amount: coins(amount.u128(), denom),
+            }
+            .into(),
+            VoteAsset::Cw20(token) => WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.into(),
+                    amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        };
+        Ok(msg)
+    }
+
+    /// Executes `msg` on `contract` paying `amount` vote tokens, as funds or with a cw20 `Send`
+    pub fn send(
+        &self,
+        contract: impl Into<String>,
+        amount: Uint128,
+        msg: &impl Serialize,
+    ) -> StdResult<WasmMsg> {
+        let contract = contract.into();
+
+        let msg = match self {
+            _ if amount.is_zero() => WasmMsg::Execute {
+                contract_addr: contract,
+                msg: to_json_binary(msg)?,
+                funds: vec![],
+            },
+            VoteAsset::Native(denom) => WasmMsg::Execute {
+                contract_addr: contract,
+                msg: to_json_binary(msg)?,
+                funds: coins(amount.u128(), denom),
+            },
+            VoteAsset::Cw20(token) => WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract,
+                    amount,
+                    msg: to_json_binary(msg)?,
+                })?,
+                funds: vec![],
+            },
+        };
+        Ok(msg)
+    }
+
+    /// Unwraps a cw20 `Receive` hook of this token into its sender paying the received amount,
+    /// and the message to execute
+    pub fn receive(
+        &self,
+        info: &MessageInfo,
+        wrapper: Cw20ReceiveMsg,
+    ) -> Option<(MessageInfo, Binary)> {
+        match self {
+            VoteAsset::Cw20(token) if *token == info.sender => {
+                let info = MessageInfo {
+                    sender: Addr::unchecked(wrapper.sender),
+                    funds: coins(wrapper.amount.u128(), self.denom()),
+                };
+                Some((info, wrapper.msg))
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,40 +1,214 @@
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw2::set_contract_version;
+use common::vote_token::{Cw20Source, VoteAsset, VoteToken};
+use cosmwasm_std::{
+    coin, ensure, from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order,
+    Reply, StdResult, Storage, SubMsg, WasmMsg,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::MinterResponse;
+use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+use semver::Version;
 
 use crate::{
     error::ContractError,
-    msg::{ExecMsg, InstantiateMsg, QueryMsg},
-    state::{Config, Correction, CONFIG, CORRECTION, TOTAL_VOTE_TOKENS_IN_CIRCULATION},
+    msg::{ExecMsg, InstantiateMsg, MigrateMsg, QueryMsg},
+    state::{
+        Config, MemberData, CLOSED_PROPOSALS, CONFIG, DELEGATED_SHARES, LOCKED_VOTE_TOKENS,
+        MEMBER_DATA, PROPOSAL_REWARDS, REWARD_SHARES, TOTAL_VOTE_TOKENS_IN_CIRCULATION,
+    },
 };
+use std::collections::HashMap;
 
 mod exec;
 mod query;
+mod reply;
+mod vote_token;
+
+#[cfg(not(feature = "token-factory"))]
+pub type VoteTokenMsg = cosmwasm_std::Empty;
+#[cfg(feature = "token-factory")]
+pub type VoteTokenMsg = common::token_factory::TokenFactoryMsg;
+
+pub type Response = cosmwasm_std::Response<VoteTokenMsg>;
+pub type CosmosMsg = cosmwasm_std::CosmosMsg<VoteTokenMsg>;
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const POINTS_SCALE: u128 = 100;
 
+const VOTE_TOKEN_INSTANTIATION_REPLY_ID: u64 = 1;
+
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let mut resp = Response::new().set_data(msg.data);
+
+    let (vote_token, vote_token_minter) = match msg.vote_token {
+        VoteToken::Native {} => {
+            resp = resp.add_messages(vote_token::create());
+            vote_token::native(&env)
+        }
+        VoteToken::Cw20(Cw20Source::Addr(addr)) => {
+            (VoteAsset::Cw20(deps.api.addr_validate(&addr)?), false)
+        }
+        VoteToken::Cw20(Cw20Source::CodeId(code_id)) => {
+            let init_msg = Cw20InstantiateMsg {
+                name: "Membership Vote Token".to_owned(),
+                symbol: "VOTE".to_owned(),
+                decimals: 0,
+                initial_balances: vec![],
+                mint: Some(MinterResponse {
+                    minter: env.contract.address.to_string(),
+                    cap: None,
+                }),
+                marketing: None,
+            };
+            let msg = WasmMsg::Instantiate {
+                admin: Some(env.contract.address.to_string()),
+                code_id,
+                msg: to_json_binary(&init_msg)?,
+                funds: vec![],
+                label: "Vote Token".to_owned(),
+            };
+            resp = resp.add_submessage(SubMsg::reply_on_success(
+                msg,
+                VOTE_TOKEN_INSTANTIATION_REPLY_ID,
+            ));
+            // replaced once the token is instantiated
+            (VoteAsset::Cw20(Addr::unchecked("")), true)
+        }
+    };
+
+    if vote_token_minter {
+        vote_token::ensure_mintable(&vote_token)?;
+    }
+
     CONFIG.save(
         deps.storage,
         &Config {
             membership_contract: info.sender,
             vote_token_price: msg.vote_token_price,
+            owner_addr: deps.api.addr_validate(&msg.owner_addr)?,
+            reward_denoms: msg.reward_denoms,
+            vote_token,
+            vote_token_minter,
         },
     )?;
 
-    CORRECTION.save(deps.storage, &Correction::default())?;
-    TOTAL_VOTE_TOKENS_IN_CIRCULATION.save(deps.storage, &msg.total_vote_tokens_in_circulation)?;
+    init_supply(deps.storage, env.block.height)?;
+
+    Ok(resp)
+}
+
+/// Every vote token starts in the reserve, membership mints them to the initial members
+fn init_supply(storage: &mut dyn Storage, height: u64) -> StdResult<()> {
+    let config = CONFIG.load(storage)?;
+    let denom = config.vote_token.denom();
+
+    TOTAL_VOTE_TOKENS_IN_CIRCULATION.save(storage, &coin(0, &denom), height)?;
+    LOCKED_VOTE_TOKENS.save(storage, &coin(0, &denom))
+}
+
+/// Proposals, and (proposal, delegate) pairs for shares out of delegate votes, whose shares
+/// were credited, or dropped as the proposal was closed
+#[derive(Default)]
+struct Settled {
+    proposals: Vec<Addr>,
+    delegated: Vec<(Addr, Addr)>,
+}
+
+/// Member data of every reward denom with the joining fees of the proposals the member, or its
+/// delegates, voted yes on credited, along with the shares settled. Shares in proposals not joined
+/// yet are kept, those in proposals closed instead settle to nothing.
+fn settled_rewards(
+    storage: &dyn Storage,
+    config: &Config,
+    proxy: &Addr,
+) -> StdResult<(HashMap<String, MemberData>, Settled)> {
+    let mut rewards = HashMap::new();
+    for denom in &config.reward_denoms {
+        let data = MEMBER_DATA
+            .may_load(storage, (proxy, denom))?
+            .unwrap_or_default();
+        rewards.insert(denom.clone(), data);
+    }
+
+    let mut settled = Settled::default();
+    for shares in REWARD_SHARES
+        .prefix(proxy)
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (proposal, shares) = shares?;
+        let Some(reward) = PROPOSAL_REWARDS.may_load(storage, &proposal)? else {
+            if CLOSED_PROPOSALS.has(storage, &proposal) {
+                settled.proposals.push(proposal);
+            }
+            continue;
+        };
 
-    Ok(Response::new().set_data(msg.data))
+        let data: &mut MemberData = rewards.entry(reward.denom).or_default();
+        data.points_balance += shares * reward.points_per_share;
+        settled.proposals.push(proposal);
+    }
+
+    for shares in DELEGATED_SHARES
+        .sub_prefix(proxy)
+        .range(storage, None, None, Order::Ascending)
+    {
+        let ((proposal, delegate), shares) = shares?;
+        let Some(reward) = PROPOSAL_REWARDS.may_load(storage, &proposal)? else {
+            if CLOSED_PROPOSALS.has(storage, &proposal) {
+                settled.delegated.push((proposal, delegate));
+            }
+            continue;
+        };
+
+        let data: &mut MemberData = rewards.entry(reward.denom).or_default();
+        data.points_balance += shares * reward.points_per_share;
+        settled.delegated.push((proposal, delegate));
+    }
+
+    // whole leftover points are moved into the reward balance
+    for data in rewards.values_mut() {
+        data.settle(POINTS_SCALE);
+    }
+
+    Ok((rewards, settled))
+}
+
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    ensure!(
+        stored.contract == CONTRACT_NAME,
+        ContractError::MigrationInvalidContract {
+            name: stored.contract
+        }
+    );
+
+    let stored_version: Version = stored.version.parse()?;
+    let version: Version = CONTRACT_VERSION.parse()?;
+
+    ensure!(
+        stored_version <= version,
+        ContractError::MigrationDowngrade {
+            version: stored.version
+        }
+    );
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION);
+
+    Ok(resp)
 }
 
 pub fn execute(
@@ -45,12 +219,69 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     use ExecMsg::*;
     match msg {
-        DistributeJoiningFee {
-            total_vote_tokens,
-            voter_tokens,
-        } => exec::distribute_joining_fee(deps, env, info, total_vote_tokens, voter_tokens),
-        BuyVoteTokens {} => exec::buy_vote_tokens(deps, env, info),
-        Withdraw {} => exec::withdraw(deps, env, info),
+        Receive(wrapper) => {
+            let config = CONFIG.load(deps.storage)?;
+            let (info, msg) = config
+                .vote_token
+                .receive(&info, wrapper)
+                .ok_or(ContractError::Unauthorized)?;
+            execute(deps, env, info, from_json(msg)?)
+        }
+        DistributeJoiningFee { total_vote_tokens } => {
+            exec::distribute_joining_fee(deps, env, info, total_vote_tokens)
+        }
+        BuyVoteTokens { amount } => exec::buy_vote_tokens(deps, env, info, amount),
+        Withdraw { amounts } => exec::withdraw(deps, env, info, amounts),
+        MintVoteTokens {
+            recipient,
+            amount,
+            holder,
+        } => exec::mint_vote_tokens(deps, env, info, recipient, amount, holder),
+        BurnVoteTokens { amount, holder } => {
+            exec::burn_vote_tokens(deps, env, info, amount, holder)
+        }
+        LockVoteTokens { voter, amount } => exec::lock_vote_tokens(deps, env, info, voter, amount),
+        UnlockVoteTokens { amount, refunds } => {
+            exec::unlock_vote_tokens(deps, env, info, amount, refunds)
+        }
+        AddRewardShares {
+            proposal,
+            voter_proxy,
+            amount,
+            height,
+        } => exec::add_reward_shares(deps, info, proposal, voter_proxy, amount, height),
+        RemoveRewardShares {
+            proposal,
+            voter_proxy,
+            amount,
+        } => exec::remove_reward_shares(deps, info, proposal, voter_proxy, amount),
+        DropRewardShares { proposal } => exec::drop_reward_shares(deps, info, proposal),
+        DropMemberShares { voter_proxy } => exec::drop_member_shares(deps, info, voter_proxy),
+        DelegateVoteTokens {
+            delegator,
+            delegate,
+            amount,
+        } => exec::delegate_vote_tokens(deps, env, info, delegator, delegate, amount),
+        UndelegateVoteTokens {
+            delegator,
+            delegate,
+            amount,
+        } => exec::undelegate_vote_tokens(deps, env, info, delegator, delegate, amount),
+        ReleaseDelegation {
+            delegator_proxy,
+            delegate_proxy,
+            amount,
+        } => exec::release_delegation(deps, env, info, delegator_proxy, delegate_proxy, amount),
+        UpdateConfig { vote_token_price } => exec::update_config(deps, info, vote_token_price),
+    }
+}
+
+pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
+    match reply.id {
+        VOTE_TOKEN_INSTANTIATION_REPLY_ID => {
+            reply::vote_token_instantiated(deps, env, reply.result.into_result())
+        }
+        id => Err(ContractError::UnrecognizedReplyId(id)),
     }
 }
 
@@ -58,6 +289,14 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     use QueryMsg::*;
 
     match msg {
-        Withdrawable { proxy } => to_binary(&query::withdrawable(deps, env, proxy)?),
+        Withdrawable { proxy } => to_json_binary(&query::withdrawable(deps, env, proxy)?),
+        Supply {} => to_json_binary(&query::supply(deps)?),
+        VotingPower { address, height } => {
+            to_json_binary(&query::voting_power(deps, env, address, height)?)
+        }
+        TotalVotingPower { height } => {
+            to_json_binary(&query::total_voting_power(deps, env, height)?)
+        }
+        Delegations { proxy } => to_json_binary(&query::delegations(deps, proxy)?),
     }
 }
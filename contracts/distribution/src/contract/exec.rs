@@ -1,81 +1,124 @@
 use std::collections::HashMap;
 
-use common::keys::{ATOM, VOTE_DENOM};
-
-use common::msg::membership::{IsMemberResp, QueryMsg as MembershipQueryMsg};
+use common::msg::{
+    membership::{IsMemberResp, IsProposalResp, OwnerProxyResp, QueryMsg as MembershipQueryMsg},
+    BuyVoteTokensResp, ProxyMemberData, VoterTokens,
+};
 
 use cosmwasm_std::{
-    coin, coins, ensure, BankMsg, Coin, DepsMut, Env, Event, MessageInfo, Response, StdResult,
-    Uint128,
+    coin, ensure, to_json_binary, Addr, BankMsg, Coin, DepsMut, Empty, Env, Event, MessageInfo,
+    Order, StdError, StdResult, Storage, Uint128,
 };
-use cw_utils::must_pay;
+use cw_utils::{may_pay, one_coin};
 
-use crate::state::TOTAL_VOTE_TOKENS_IN_CIRCULATION;
+use crate::state::{
+    CLOSED_PROPOSALS, DELEGATED_SHARES, DELEGATES, DELEGATIONS, DROPPED_SHARES, LOCKED_VOTE_TOKENS,
+    TOTAL_VOTE_TOKENS_IN_CIRCULATION, VOTING_POWER,
+};
 use crate::{
     error::ContractError,
-    state::{MemberData, CONFIG, CORRECTION, MEMBER_DATA},
+    state::{
+        Config, MemberData, ProposalReward, CONFIG, CORRECTION, MEMBER_DATA, PROPOSAL_REWARDS,
+        REWARD_SHARES,
+    },
 };
 
-use super::POINTS_SCALE;
+use super::{vote_token, Response, POINTS_SCALE};
 
 pub fn distribute_joining_fee(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     total_vote_tokens: Coin,
-    voter_tokens: HashMap<String, Coin>,
 ) -> Result<Response, ContractError> {
-    let fee_to_distribute = must_pay(&info, ATOM)?.u128();
-    // Membership at the time of instantiation of new proxy for new joining member transfers new member tokens directly
-    // Proposal passed total_vote_tokens & vote_tokens (share of each voter)
-    // this helps to calculate total weight and distribute rewards among voters
-    let total_weight = total_vote_tokens.amount.u128();
-
+    let config = CONFIG.load(deps.storage)?;
+    let fee = one_coin(&info)?;
+    ensure!(
+        config.reward_denoms.contains(&fee.denom),
+        ContractError::JoiningFeeDenomNotAccepted { denom: fee.denom }
+    );
+
+    // only proposals instantiated by membership pay joining fees
+    let is_proposal: IsProposalResp = deps.querier.query_wasm_smart(
+        &config.membership_contract,
+        &MembershipQueryMsg::IsProposal {
+            addr: info.sender.to_string(),
+        },
+    )?;
+    ensure!(is_proposal.ok, ContractError::Unauthorized);
+
+    // the proposal pays once, its yes voters settle their share of it lazily
+    let proposal = info.sender;
+    ensure!(
+        !PROPOSAL_REWARDS.has(deps.storage, &proposal),
+        ContractError::JoiningFeeAlreadyDistributed {
+            proposal: proposal.into_string()
+        }
+    );
+
+    let fee_to_distribute = fee.amount.u128();
+    // total yes vote tokens of the proposal, each voter's reward shares were added as it voted,
+    // those of members who left since were dropped
+    let dropped = DROPPED_SHARES
+        .may_load(deps.storage, &proposal)?
+        .unwrap_or_default();
+    DROPPED_SHARES.remove(deps.storage, &proposal);
+    let total_weight = total_vote_tokens
+        .amount
+        .checked_sub(dropped)
+        .map_err(StdError::from)?
+        .u128();
+
+    // the fee of a proposal whose yes voters all left is kept undistributed
     let total_points = fee_to_distribute * POINTS_SCALE;
-    let ppw = total_points / total_weight;
+    let ppw = match total_points.checked_div(total_weight) {
+        Some(ppw) => ppw,
+        None if !dropped.is_zero() => 0,
+        None => {
+            return Err(ContractError::JoiningFeeWithoutYesVotes {
+                proposal: proposal.into_string(),
+            })
+        }
+    };
 
     let points_to_distribute = ppw * total_weight;
 
-    let mut correction = CORRECTION.load(deps.storage)?;
+    let mut correction = CORRECTION
+        .may_load(deps.storage, &fee.denom)?
+        .unwrap_or_default();
     correction.points_balance += Uint128::new(total_points - points_to_distribute);
-    CORRECTION.save(deps.storage, &correction)?;
-
-    let events: Vec<_> = voter_tokens
-        .into_iter()
-        .map(|(addr, votes)| -> Result<_, ContractError> {
-            let addr = deps.api.addr_validate(&addr)?;
-            let mut data = MEMBER_DATA
-                .may_load(deps.storage, &addr)?
-                .unwrap_or(MemberData::default().with_reward_balance(coin(0, ATOM)));
-            let weight = votes.amount.u128();
-            let points = weight * ppw;
-            let amount = points / POINTS_SCALE;
-            data.points_balance += Uint128::new(points % POINTS_SCALE);
-            data.reward_balance.amount += Uint128::new(amount);
-
-            MEMBER_DATA.save(deps.storage, &addr, &data)?;
-
-            let event = Event::new("reward_distribution")
-                .add_attribute("voter_proxy", addr.as_str())
-                .add_attribute("voter_weight", votes.amount.to_string())
-                .add_attribute("reward_amount", amount.to_string())
-                .add_attribute("reward_token", ATOM);
-            Ok(event)
-        })
-        .collect::<Result<_, _>>()?;
+    CORRECTION.save(deps.storage, &fee.denom, &correction)?;
+
+    let reward = ProposalReward {
+        denom: fee.denom.clone(),
+        points_per_share: Uint128::new(ppw),
+    };
+    PROPOSAL_REWARDS.save(deps.storage, &proposal, &reward)?;
+
+    let event = Event::new("reward_distribution")
+        .add_attribute("proposal", proposal.as_str())
+        .add_attribute("total_weight", total_weight.to_string())
+        .add_attribute("points_per_weight", reward.points_per_share.to_string())
+        .add_attribute("reward_amount", fee.amount.to_string())
+        .add_attribute("reward_token", &fee.denom);
 
     let resp = Response::new()
-        .add_events(events)
+        .add_event(event)
         .add_attribute("action", "distribute_joining_fee")
-        .add_attribute("sender", info.sender.as_str());
+        .add_attribute("sender", proposal.as_str());
 
     Ok(resp)
 }
 
-pub fn withdraw(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn withdraw(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    amounts: Option<Vec<Coin>>,
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let is_member: IsMemberResp = deps.querier.query_wasm_smart(
-        config.membership_contract,
+        &config.membership_contract,
         &MembershipQueryMsg::IsMember {
             addr: info.sender.to_string(),
         },
@@ -83,25 +126,57 @@ pub fn withdraw(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response,
 
     ensure!(is_member.ok, ContractError::Unauthorized);
 
-    let mut data = MEMBER_DATA
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or(MemberData::default().with_reward_balance(coin(0, ATOM)));
-
-    let reward_amount =
-        data.reward_balance.amount + data.points_balance / Uint128::new(POINTS_SCALE);
-    data.reward_balance.amount -= reward_amount;
-    data.points_balance = data.points_balance % Uint128::new(POINTS_SCALE);
+    if let Some(unknown) = amounts
+        .iter()
+        .flatten()
+        .find(|requested| !config.reward_denoms.contains(&requested.denom))
+    {
+        return Err(ContractError::InsufficientRewards {
+            available: coin(0, &unknown.denom),
+        });
+    }
 
-    MEMBER_DATA.save(deps.storage, &info.sender, &data)?;
+    let mut rewards = settle_rewards(deps.storage, &config, &info.sender)?;
+    let mut withdrawn = vec![];
+
+    for denom in &config.reward_denoms {
+        let data = rewards.entry(denom.clone()).or_default();
+        let available = data.reward_balance;
+        let reward_amount = match &amounts {
+            Some(amounts) => amounts
+                .iter()
+                .filter(|requested| requested.denom == *denom)
+                .map(|requested| requested.amount)
+                .sum(),
+            None => available,
+        };
+
+        ensure!(
+            reward_amount <= available,
+            ContractError::InsufficientRewards {
+                available: coin(available.u128(), denom)
+            }
+        );
+
+        data.reward_balance -= reward_amount;
+        MEMBER_DATA.save(deps.storage, (&info.sender, denom), data)?;
+
+        if !reward_amount.is_zero() {
+            withdrawn.push(coin(reward_amount.u128(), denom));
+        }
+    }
 
     let mut resp = Response::new()
         .add_attribute("action", "withdraw")
-        .add_attribute("sender", info.sender.as_str());
+        .add_attribute("sender", info.sender.as_str())
+        .set_data(to_json_binary(&withdrawn)?);
 
-    if reward_amount.u128() > 0 {
+    if !withdrawn.is_empty() {
+        let amount: Vec<_> = withdrawn.iter().map(Coin::to_string).collect();
+        resp = resp.add_attribute("amount", amount.join(","));
         resp = resp.add_message(BankMsg::Send {
             to_address: info.sender.into(),
-            amount: coins(reward_amount.u128(), ATOM),
+            amount: withdrawn,
         });
     }
 
@@ -112,10 +187,11 @@ pub fn buy_vote_tokens(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    amount: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let is_member: IsMemberResp = deps.querier.query_wasm_smart(
-        config.membership_contract,
+        &config.membership_contract,
         &MembershipQueryMsg::IsMember {
             addr: info.sender.to_string(),
         },
@@ -123,39 +199,723 @@ pub fn buy_vote_tokens(
 
     ensure!(is_member.ok, ContractError::Unauthorized);
 
-    let mut data = MEMBER_DATA
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or(MemberData::default().with_reward_balance(coin(0, ATOM)));
+    // vote tokens are paid with rewards in the price denom
+    let price = &config.vote_token_price;
+    let mut data = settle_rewards(deps.storage, &config, &info.sender)?
+        .remove(&price.denom)
+        .unwrap_or_default();
+
+    let available_rewards = data.reward_balance;
+    let vote_amount = amount.unwrap_or(available_rewards / price.amount);
+    let insufficient = || ContractError::InsufficientRewards {
+        available: coin(available_rewards.u128(), &price.denom),
+    };
+    let cost = vote_amount
+        .checked_mul(price.amount)
+        .map_err(|_| insufficient())?;
+
+    ensure!(
+        !vote_amount.is_zero() && cost <= available_rewards,
+        insufficient()
+    );
+
+    let issue_msg = vote_token::issue(deps.as_ref(), &env, &config, &info.sender, vote_amount)?;
+
+    data.reward_balance -= cost;
+    MEMBER_DATA.save(deps.storage, (&info.sender, &price.denom), &data)?;
+
+    TOTAL_VOTE_TOKENS_IN_CIRCULATION.update(
+        deps.storage,
+        env.block.height,
+        |c| -> StdResult<_> {
+            let mut c = c.unwrap_or_default();
+            c.amount += vote_amount;
+            Ok(c)
+        },
+    )?;
 
-    let reward_amount =
-        data.reward_balance.amount + data.points_balance / Uint128::new(POINTS_SCALE);
-    data.reward_balance.amount -= reward_amount;
-    data.points_balance = data.points_balance % Uint128::new(POINTS_SCALE);
+    // the proxy keeps bought vote tokens, they count for its owner
+    let member: OwnerProxyResp = deps.querier.query_wasm_smart(
+        &config.membership_contract,
+        &MembershipQueryMsg::MemberByProxy {
+            proxy: info.sender.to_string(),
+        },
+    )?;
+    add_voting_power(
+        deps.storage,
+        env.block.height,
+        &Addr::unchecked(member.owner),
+        vote_amount,
+    )?;
 
-    let vote_amount = reward_amount / config.vote_token_price.amount;
-    data.reward_balance.amount += reward_amount % config.vote_token_price.amount;
+    let bought = BuyVoteTokensResp {
+        vote_tokens: config.vote_token.coin(vote_amount),
+        change: coin(data.reward_balance.u128(), &price.denom),
+    };
 
-    MEMBER_DATA.save(deps.storage, &info.sender, &data)?;
+    let proceeds = coin(cost.u128(), &price.denom);
+    let proceeds_event = Event::new("vote_token_proceeds")
+        .add_attribute("owner_addr", config.owner_addr.as_str())
+        .add_attribute("amount", proceeds.to_string());
 
-    let mut resp = Response::new()
+    let resp = Response::new()
+        .add_messages(issue_msg)
+        .add_message(BankMsg::Send {
+            to_address: config.owner_addr.into_string(),
+            amount: vec![proceeds],
+        })
+        .add_event(proceeds_event)
         .add_attribute("action", "buy_vote_token")
-        .add_attribute("sender", info.sender.as_str());
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("vote_tokens", bought.vote_tokens.to_string())
+        .add_attribute("change", bought.change.to_string())
+        .set_data(to_json_binary(&bought)?);
 
-    let available_vote_amount = deps
-        .querier
-        .query_balance(env.contract.address, VOTE_DENOM)?
-        .amount;
+    Ok(resp)
+}
 
-    if vote_amount.u128() > 0 && available_vote_amount > vote_amount {
-        TOTAL_VOTE_TOKENS_IN_CIRCULATION.update(deps.storage, |mut c| -> StdResult<_> {
-            c.amount += vote_amount;
+pub fn mint_vote_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    holder: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let holder = holder
+        .map(|holder| deps.api.addr_validate(&holder))
+        .transpose()?
+        .unwrap_or_else(|| recipient.clone());
+    let issue_msg = vote_token::issue(deps.as_ref(), &env, &config, &recipient, amount)?;
+
+    TOTAL_VOTE_TOKENS_IN_CIRCULATION.update(
+        deps.storage,
+        env.block.height,
+        |c| -> StdResult<_> {
+            let mut c = c.unwrap_or_default();
+            c.amount += amount;
+            Ok(c)
+        },
+    )?;
+    add_voting_power(deps.storage, env.block.height, &holder, amount)?;
+
+    let resp = Response::new()
+        .add_messages(issue_msg)
+        .add_attribute("action", "mint_vote_tokens")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("recipient", recipient.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+/// Takes `amount` out of circulation. Vote tokens sent along go back to the reserve, the rest
+/// were either sent to the reserve beforehand or are held by accounts that are no longer members.
+pub fn burn_vote_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    holder: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let paid = may_pay(&info, &config.vote_token.denom())?;
+    ensure!(paid <= amount, ContractError::BurnAmountBelowPaid { paid });
+
+    TOTAL_VOTE_TOKENS_IN_CIRCULATION.update(
+        deps.storage,
+        env.block.height,
+        |c| -> StdResult<_> {
+            let mut c = c.unwrap_or_default();
+            c.amount = c.amount.checked_sub(amount)?;
             Ok(c)
+        },
+    )?;
+
+    if let Some(holder) = holder {
+        let holder = deps.api.addr_validate(&holder)?;
+        VOTING_POWER.remove(deps.storage, &holder, env.block.height)?;
+    }
+
+    let reclaim_msg = vote_token::reclaim(deps.as_ref(), &env, &config)?;
+
+    let resp = Response::new()
+        .add_messages(reclaim_msg)
+        .add_attribute("action", "burn_vote_tokens")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+/// Records vote tokens escrowed in a proposal, they stay in circulation
+pub fn lock_vote_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    voter: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    // vote tokens which did not come from distribution carry no voting power, they cannot be
+    // escrowed
+    let voter = deps.api.addr_validate(&voter)?;
+    take_voting_power(deps.storage, env.block.height, &voter, amount)?;
+
+    LOCKED_VOTE_TOKENS.update(deps.storage, |mut c| -> StdResult<_> {
+        c.amount += amount;
+        Ok(c)
+    })?;
+
+    let resp = Response::new()
+        .add_attribute("action", "lock_vote_tokens")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("voter", voter.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+/// Records vote tokens released from a proposal escrow
+pub fn unlock_vote_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    refunds: Vec<VoterTokens>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    for refund in refunds {
+        let voter = deps.api.addr_validate(&refund.voter)?;
+        add_voting_power(deps.storage, env.block.height, &voter, refund.amount)?;
+    }
+
+    LOCKED_VOTE_TOKENS.update(deps.storage, |mut c| -> StdResult<_> {
+        c.amount = c.amount.checked_sub(amount)?;
+        Ok(c)
+    })?;
+
+    let resp = Response::new()
+        .add_attribute("action", "unlock_vote_tokens")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+/// Records yes vote tokens of a member proposal voter, they are its shares of the joining fee.
+/// Shares of a delegate are split with its delegators, pro-rata to the vote tokens delegated and
+/// its own voting power at the proposal start `height`.
+pub fn add_reward_shares(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal: String,
+    voter_proxy: String,
+    amount: Uint128,
+    height: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let proposal = deps.api.addr_validate(&proposal)?;
+    let voter_proxy = deps.api.addr_validate(&voter_proxy)?;
+
+    let delegations = delegations_at_height(deps.storage, &voter_proxy, height)?;
+    let parts = if delegations.is_empty() {
+        vec![amount]
+    } else {
+        let member: OwnerProxyResp = deps.querier.query_wasm_smart(
+            &config.membership_contract,
+            &MembershipQueryMsg::MemberByProxy {
+                proxy: voter_proxy.to_string(),
+            },
+        )?;
+        let power = VOTING_POWER
+            .may_load_at_height(deps.storage, &Addr::unchecked(member.owner), height)?
+            .unwrap_or_default();
+        let delegated: Uint128 = delegations.iter().map(|(_, delegated)| delegated).sum();
+
+        let weights: Vec<_> = std::iter::once(power.saturating_sub(delegated))
+            .chain(delegations.iter().map(|(_, delegated)| *delegated))
+            .collect();
+        split_pro_rata(amount, &weights)
+    };
+
+    REWARD_SHARES.update(
+        deps.storage,
+        (&voter_proxy, &proposal),
+        |shares| -> StdResult<_> { Ok(shares.unwrap_or_default() + parts[0]) },
+    )?;
+
+    for ((delegator, _), part) in delegations.iter().zip(&parts[1..]) {
+        if part.is_zero() {
+            continue;
+        }
+        DELEGATED_SHARES.update(
+            deps.storage,
+            (delegator, &proposal, &voter_proxy),
+            |shares| -> StdResult<_> { Ok(shares.unwrap_or_default() + part) },
+        )?;
+    }
+
+    let resp = Response::new()
+        .add_attribute("action", "add_reward_shares")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("proposal", proposal.as_str())
+        .add_attribute("voter_proxy", voter_proxy.as_str())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("delegated", (amount - parts[0]).to_string());
+
+    Ok(resp)
+}
+
+/// Takes back shares of a yes vote retracted before the proposal was joined, from the voter and
+/// its delegators pro-rata to the shares they got out of its votes
+pub fn remove_reward_shares(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal: String,
+    voter_proxy: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let proposal = deps.api.addr_validate(&proposal)?;
+    let voter_proxy = deps.api.addr_validate(&voter_proxy)?;
+
+    let own = REWARD_SHARES
+        .may_load(deps.storage, (&voter_proxy, &proposal))?
+        .unwrap_or_default();
+    let delegated = DELEGATIONS
+        .prefix(&voter_proxy)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|delegator| -> StdResult<_> {
+            let delegator = delegator?;
+            let shares = DELEGATED_SHARES
+                .may_load(deps.storage, (&delegator, &proposal, &voter_proxy))?
+                .unwrap_or_default();
+            Ok((delegator, shares))
+        })
+        .filter(|shares| !matches!(shares, Ok((_, shares)) if shares.is_zero()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let weights: Vec<_> = std::iter::once(own)
+        .chain(delegated.iter().map(|(_, shares)| *shares))
+        .collect();
+    let parts = split_pro_rata(amount, &weights);
+
+    let shares = own.checked_sub(parts[0]).map_err(StdError::from)?;
+    if shares.is_zero() {
+        REWARD_SHARES.remove(deps.storage, (&voter_proxy, &proposal));
+    } else {
+        REWARD_SHARES.save(deps.storage, (&voter_proxy, &proposal), &shares)?;
+    }
+
+    // parts never exceed the shares they are taken from, anything beyond is the voter's
+    for ((delegator, shares), part) in delegated.iter().zip(&parts[1..]) {
+        let key = (delegator, &proposal, &voter_proxy);
+        if shares == part {
+            DELEGATED_SHARES.remove(deps.storage, key);
+        } else {
+            DELEGATED_SHARES.save(deps.storage, key, &(shares - part))?;
+        }
+    }
+
+    let resp = Response::new()
+        .add_attribute("action", "remove_reward_shares")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("proposal", proposal.as_str())
+        .add_attribute("voter_proxy", voter_proxy.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+/// Marks a member proposal closed without being joined, shares in it are removed lazily by
+/// their holders as they settle
+pub fn drop_reward_shares(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let proposal = deps.api.addr_validate(&proposal)?;
+    CLOSED_PROPOSALS.save(deps.storage, &proposal, &Empty {})?;
+    DROPPED_SHARES.remove(deps.storage, &proposal);
+
+    let resp = Response::new()
+        .add_attribute("action", "drop_reward_shares")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("proposal", proposal.as_str());
+
+    Ok(resp)
+}
+
+/// Drops the shares of a member who left in proposals not joined yet, those of joined ones were
+/// settled as it withdrew its rewards on leaving
+pub fn drop_member_shares(
+    deps: DepsMut,
+    info: MessageInfo,
+    voter_proxy: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let voter_proxy = deps.api.addr_validate(&voter_proxy)?;
+
+    let own = REWARD_SHARES
+        .prefix(&voter_proxy)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let delegated = DELEGATED_SHARES
+        .sub_prefix(&voter_proxy)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (proposal, _) in &own {
+        REWARD_SHARES.remove(deps.storage, (&voter_proxy, proposal));
+    }
+    for ((proposal, delegate), _) in &delegated {
+        DELEGATED_SHARES.remove(deps.storage, (&voter_proxy, proposal, delegate));
+    }
+
+    let mut dropped = Uint128::zero();
+    let shares = own.into_iter().chain(
+        delegated
+            .into_iter()
+            .map(|((proposal, _), shares)| (proposal, shares)),
+    );
+    for (proposal, shares) in shares {
+        if CLOSED_PROPOSALS.has(deps.storage, &proposal)
+            || PROPOSAL_REWARDS.has(deps.storage, &proposal)
+        {
+            continue;
+        }
+        DROPPED_SHARES.update(deps.storage, &proposal, |dropped| -> StdResult<_> {
+            Ok(dropped.unwrap_or_default() + shares)
         })?;
-        resp = resp.add_message(BankMsg::Send {
-            to_address: info.sender.into(),
-            amount: coins(vote_amount.u128(), VOTE_DENOM),
-        });
+        dropped += shares;
+    }
+
+    let resp = Response::new()
+        .add_attribute("action", "drop_member_shares")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("voter_proxy", voter_proxy.as_str())
+        .add_attribute("dropped", dropped.to_string());
+
+    Ok(resp)
+}
+
+/// Records vote tokens the delegator proxy handed to the delegate proxy
+pub fn delegate_vote_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegator: ProxyMemberData,
+    delegate: ProxyMemberData,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let delegator_proxy = deps.api.addr_validate(&delegator.proxy_addr)?;
+    let delegate_proxy = deps.api.addr_validate(&delegate.proxy_addr)?;
+
+    DELEGATIONS.update(
+        deps.storage,
+        (&delegate_proxy, &delegator_proxy),
+        env.block.height,
+        |delegated| -> StdResult<_> { Ok(delegated.unwrap_or_default() + amount) },
+    )?;
+    DELEGATES.save(deps.storage, (&delegator_proxy, &delegate_proxy), &Empty {})?;
+
+    move_voting_power(
+        deps.storage,
+        env.block.height,
+        &deps.api.addr_validate(&delegator.owner_addr)?,
+        &deps.api.addr_validate(&delegate.owner_addr)?,
+        amount,
+    )?;
+
+    let resp = Response::new()
+        .add_attribute("action", "delegate_vote_tokens")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("delegator_proxy", delegator_proxy.as_str())
+        .add_attribute("delegate_proxy", delegate_proxy.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+/// Takes `amount` the delegate proxy hands back off a delegation
+pub fn undelegate_vote_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegator: ProxyMemberData,
+    delegate: ProxyMemberData,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let delegator_proxy = deps.api.addr_validate(&delegator.proxy_addr)?;
+    let delegate_proxy = deps.api.addr_validate(&delegate.proxy_addr)?;
+
+    let delegated = DELEGATIONS
+        .may_load(deps.storage, (&delegate_proxy, &delegator_proxy))?
+        .unwrap_or_default();
+    ensure!(
+        amount <= delegated,
+        ContractError::InsufficientDelegation { delegated }
+    );
+
+    DELEGATIONS.save(
+        deps.storage,
+        (&delegate_proxy, &delegator_proxy),
+        &(delegated - amount),
+        env.block.height,
+    )?;
+    if delegated == amount {
+        DELEGATES.remove(deps.storage, (&delegator_proxy, &delegate_proxy));
+    }
+
+    move_voting_power(
+        deps.storage,
+        env.block.height,
+        &deps.api.addr_validate(&delegate.owner_addr)?,
+        &deps.api.addr_validate(&delegator.owner_addr)?,
+        amount,
+    )?;
+
+    let resp = Response::new()
+        .add_attribute("action", "undelegate_vote_tokens")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("delegator_proxy", delegator_proxy.as_str())
+        .add_attribute("delegate_proxy", delegate_proxy.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+/// Takes `amount` off a delegation the delegate proxy does not hand back, its voting power
+/// already went with the escrow or the member who left
+pub fn release_delegation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegator_proxy: String,
+    delegate_proxy: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    let delegator_proxy = deps.api.addr_validate(&delegator_proxy)?;
+    let delegate_proxy = deps.api.addr_validate(&delegate_proxy)?;
+
+    let delegated = DELEGATIONS
+        .may_load(deps.storage, (&delegate_proxy, &delegator_proxy))?
+        .unwrap_or_default();
+    ensure!(
+        amount <= delegated,
+        ContractError::InsufficientDelegation { delegated }
+    );
+
+    DELEGATIONS.save(
+        deps.storage,
+        (&delegate_proxy, &delegator_proxy),
+        &(delegated - amount),
+        env.block.height,
+    )?;
+    if delegated == amount {
+        DELEGATES.remove(deps.storage, (&delegator_proxy, &delegate_proxy));
+    }
+
+    let resp = Response::new()
+        .add_attribute("action", "release_delegation")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("delegator_proxy", delegator_proxy.as_str())
+        .add_attribute("delegate_proxy", delegate_proxy.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+/// Delegations to the proxy at the beginning of `height`
+fn delegations_at_height(
+    storage: &dyn Storage,
+    delegate_proxy: &Addr,
+    height: u64,
+) -> StdResult<Vec<(Addr, Uint128)>> {
+    DELEGATIONS
+        .prefix(delegate_proxy)
+        .keys(storage, None, None, Order::Ascending)
+        .map(|delegator| -> StdResult<_> {
+            let delegator = delegator?;
+            let delegated = DELEGATIONS
+                .may_load_at_height(storage, (delegate_proxy, &delegator), height)?
+                .unwrap_or_default();
+            Ok((delegator, delegated))
+        })
+        .filter(|delegation| !matches!(delegation, Ok((_, delegated)) if delegated.is_zero()))
+        .collect()
+}
+
+/// Splits `amount` pro-rata to `weights`, rounding down and handing the remainder out in order.
+/// Parts add up to `amount` and do not exceed their weights, anything beyond goes to the first.
+fn split_pro_rata(amount: Uint128, weights: &[Uint128]) -> Vec<Uint128> {
+    let total: Uint128 = weights.iter().sum();
+    let mut parts: Vec<_> = weights
+        .iter()
+        .map(|weight| {
+            if total.is_zero() {
+                Uint128::zero()
+            } else {
+                amount.multiply_ratio(*weight, total)
+            }
+        })
+        .collect();
+
+    let mut remainder = amount - parts.iter().sum::<Uint128>();
+    for (part, weight) in parts.iter_mut().zip(weights) {
+        let room = weight.saturating_sub(*part).min(remainder);
+        *part += room;
+        remainder -= room;
+    }
+    parts[0] += remainder;
+
+    parts
+}
+
+/// Moves `amount` of voting power, vote tokens which did not come from distribution carry none
+/// so they cannot be delegated
+fn move_voting_power(
+    storage: &mut dyn Storage,
+    height: u64,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    take_voting_power(storage, height, from, amount)?;
+    add_voting_power(storage, height, to, amount)?;
+
+    Ok(())
+}
+
+fn take_voting_power(
+    storage: &mut dyn Storage,
+    height: u64,
+    member: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    VOTING_POWER.update(storage, member, height, |power| {
+        let power = power.unwrap_or_default();
+        power
+            .checked_sub(amount)
+            .map_err(|_| ContractError::InsufficientVotingPower { power })
+    })
+}
+
+fn add_voting_power(
+    storage: &mut dyn Storage,
+    height: u64,
+    member: &Addr,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    VOTING_POWER.update(storage, member, height, |power| -> StdResult<_> {
+        Ok(power.unwrap_or_default() + amount)
+    })
+}
+
+/// Credits the member with the joining fees of the joined proposals it voted yes on
+fn settle_rewards(
+    storage: &mut dyn Storage,
+    config: &Config,
+    proxy: &Addr,
+) -> StdResult<HashMap<String, MemberData>> {
+    let (rewards, settled) = super::settled_rewards(storage, config, proxy)?;
+    for proposal in settled.proposals {
+        REWARD_SHARES.remove(storage, (proxy, &proposal));
+    }
+    for (proposal, delegate) in settled.delegated {
+        DELEGATED_SHARES.remove(storage, (proxy, &proposal, &delegate));
     }
 
+    for (denom, data) in &rewards {
+        MEMBER_DATA.save(storage, (proxy, denom), data)?;
+    }
+
+    Ok(rewards)
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    vote_token_price: Coin,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == config.membership_contract,
+        ContractError::Unauthorized
+    );
+
+    config.vote_token_price = vote_token_price;
+    CONFIG.save(deps.storage, &config)?;
+
+    let event = Event::new("config_update")
+        .add_attribute("vote_token_price", config.vote_token_price.to_string());
+
+    let resp = Response::new()
+        .add_event(event)
+        .add_attribute("action", "update_config")
+        .add_attribute("sender", info.sender.as_str());
+
     Ok(resp)
 }
@@ -1,24 +1,99 @@
 use common::msg::WithdrawableResp;
-use cosmwasm_std::{Addr, Deps, Env, StdResult, Uint128};
+use cosmwasm_std::{coin, Addr, Deps, Env, Order, StdResult};
 
-use crate::state::MEMBER_DATA;
-
-use super::POINTS_SCALE;
+use crate::msg::{DelegationResp, DelegationsResp, SupplyResp, VotingPowerResp};
+use crate::state::{
+    CONFIG, DELEGATES, DELEGATIONS, LOCKED_VOTE_TOKENS, TOTAL_VOTE_TOKENS_IN_CIRCULATION,
+    VOTING_POWER,
+};
 
 pub fn withdrawable(deps: Deps, _env: Env, proxy: String) -> StdResult<WithdrawableResp> {
     let proxy = Addr::unchecked(proxy);
+    let config = CONFIG.load(deps.storage)?;
+    let (rewards, _) = super::settled_rewards(deps.storage, &config, &proxy)?;
 
-    let member_data = MEMBER_DATA
-        .may_load(deps.storage, &proxy)?
-        .unwrap_or_default();
+    let mut funds = vec![];
+    for denom in config.reward_denoms {
+        let balance = rewards[&denom].reward_balance;
+        if !balance.is_zero() {
+            funds.push(coin(balance.u128(), denom));
+        }
+    }
 
-    let mut reward_funds = member_data.reward_balance.clone();
-    reward_funds.amount += member_data.points_balance / Uint128::new(POINTS_SCALE);
+    Ok(WithdrawableResp { funds })
+}
 
-    if reward_funds.amount.is_zero() {
-        return Ok(WithdrawableResp::default());
-    }
-    Ok(WithdrawableResp {
-        funds: Some(reward_funds),
+pub fn voting_power(
+    deps: Deps,
+    env: Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerResp> {
+    let address = deps.api.addr_validate(&address)?;
+    let power = match height {
+        Some(height) => VOTING_POWER.may_load_at_height(deps.storage, &address, height)?,
+        None => VOTING_POWER.may_load(deps.storage, &address)?,
+    };
+
+    Ok(VotingPowerResp {
+        power: power.unwrap_or_default(),
+        height: height.unwrap_or(env.block.height),
+    })
+}
+
+pub fn total_voting_power(deps: Deps, env: Env, height: Option<u64>) -> StdResult<VotingPowerResp> {
+    let circulating = match height {
+        Some(height) => {
+            TOTAL_VOTE_TOKENS_IN_CIRCULATION.may_load_at_height(deps.storage, height)?
+        }
+        None => TOTAL_VOTE_TOKENS_IN_CIRCULATION.may_load(deps.storage)?,
+    };
+
+    Ok(VotingPowerResp {
+        power: circulating.map(|c| c.amount).unwrap_or_default(),
+        height: height.unwrap_or(env.block.height),
+    })
+}
+
+pub fn supply(deps: Deps) -> StdResult<SupplyResp> {
+    Ok(SupplyResp {
+        circulating: TOTAL_VOTE_TOKENS_IN_CIRCULATION.load(deps.storage)?,
+        locked: LOCKED_VOTE_TOKENS.load(deps.storage)?,
+    })
+}
+
+pub fn delegations(deps: Deps, proxy: String) -> StdResult<DelegationsResp> {
+    let proxy = deps.api.addr_validate(&proxy)?;
+
+    let delegated = DELEGATES
+        .prefix(&proxy)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|delegate| -> StdResult<_> {
+            let delegate = delegate?;
+            let amount = DELEGATIONS.load(deps.storage, (&delegate, &proxy))?;
+            Ok(DelegationResp {
+                proxy: delegate.into(),
+                amount,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    // undelegated ones are kept at zero
+    let received = DELEGATIONS
+        .prefix(&proxy)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|delegation| !matches!(delegation, Ok((_, amount)) if amount.is_zero()))
+        .map(|delegation| -> StdResult<_> {
+            let (delegator, amount) = delegation?;
+            Ok(DelegationResp {
+                proxy: delegator.into(),
+                amount,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(DelegationsResp {
+        delegated,
+        received,
     })
 }
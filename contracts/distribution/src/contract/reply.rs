@@ -0,0 +1,27 @@
+use common::vote_token::VoteAsset;
+use cosmwasm_std::{Addr, DepsMut, Env, StdError, SubMsgResponse};
+use cw_utils::parse_instantiate_response_data;
+
+use crate::{error::ContractError, state::CONFIG};
+
+use super::Response;
+
+pub fn vote_token_instantiated(
+    deps: DepsMut,
+    env: Env,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
+    let response = reply.map_err(StdError::generic_err)?;
+    let data = response.data.ok_or(ContractError::DataMissing)?;
+    let response = parse_instantiate_response_data(&data)?;
+    let token = Addr::unchecked(response.contract_address);
+
+    CONFIG.update(deps.storage, |mut config| -> Result<_, StdError> {
+        config.vote_token = VoteAsset::Cw20(token.clone());
+        Ok(config)
+    })?;
+    super::init_supply(deps.storage, env.block.height)?;
+
+    let resp = Response::new().add_attribute("vote_token", token.as_str());
+    Ok(resp)
+}
@@ -0,0 +1,153 @@
+use common::vote_token::VoteAsset;
+use cosmwasm_std::{ensure, to_json_binary, Addr, Deps, Env, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+#[cfg(not(feature = "token-factory"))]
+use common::keys::VOTE_DENOM;
+#[cfg(feature = "token-factory")]
+use common::token_factory::{factory_denom, TokenFactoryMsg, VOTE_SUBDENOM};
+
+use super::CosmosMsg;
+use crate::{error::ContractError, state::Config};
+
+/// The `VOTE` reserve is prefunded by membership
+#[cfg(not(feature = "token-factory"))]
+pub fn native(_env: &Env) -> (VoteAsset, bool) {
+    (VoteAsset::Native(VOTE_DENOM.to_owned()), false)
+}
+
+/// The factory denom is minted by distribution
+#[cfg(feature = "token-factory")]
+pub fn native(env: &Env) -> (VoteAsset, bool) {
+    let denom = factory_denom(&env.contract.address, VOTE_SUBDENOM);
+    (VoteAsset::Native(denom), true)
+}
+
+#[cfg(not(feature = "token-factory"))]
+pub fn create() -> Option<CosmosMsg> {
+    None
+}
+
+#[cfg(feature = "token-factory")]
+pub fn create() -> Option<CosmosMsg> {
+    let msg = TokenFactoryMsg::CreateDenom {
+        subdenom: VOTE_SUBDENOM.to_owned(),
+    };
+    Some(CosmosMsg::Custom(msg))
+}
+
+/// Mints vote tokens if distribution is the minter, otherwise sends them out of the reserve
+pub fn issue(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<Option<CosmosMsg>, ContractError> {
+    if !config.vote_token_minter {
+        let available = config
+            .vote_token
+            .balance(&deps.querier, &env.contract.address)?;
+
+        ensure!(
+            amount <= available,
+            ContractError::InsufficientVoteTokenSupply { available }
+        );
+    }
+
+    if amount.is_zero() {
+        return Ok(None);
+    }
+
+    let msg = if config.vote_token_minter {
+        mint(&config.vote_token, recipient, amount)?
+    } else {
+        config.vote_token.transfer(recipient, amount)?
+    };
+    Ok(Some(msg))
+}
+
+/// Vote tokens sent back stay in the reserve, unless distribution is the minter and there is no
+/// reserve - then whatever was sent back is burned
+pub fn reclaim(deps: Deps, env: &Env, config: &Config) -> Result<Option<CosmosMsg>, ContractError> {
+    if !config.vote_token_minter {
+        return Ok(None);
+    }
+
+    let held = config
+        .vote_token
+        .balance(&deps.querier, &env.contract.address)?;
+
+    if held.is_zero() {
+        return Ok(None);
+    }
+
+    let msg = burn(&config.vote_token, env, held)?;
+    Ok(Some(msg))
+}
+
+/// Distribution mints and burns the cw20 vote tokens it instantiated, native ones only as the
+/// creator of the factory denom
+pub fn ensure_mintable(vote_token: &VoteAsset) -> Result<(), ContractError> {
+    match vote_token {
+        VoteAsset::Cw20(_) => Ok(()),
+        #[cfg(feature = "token-factory")]
+        VoteAsset::Native(_) => Ok(()),
+        #[cfg(not(feature = "token-factory"))]
+        VoteAsset::Native(denom) => Err(ContractError::VoteTokenNotMintable {
+            denom: denom.clone(),
+        }),
+    }
+}
+
+fn mint(
+    vote_token: &VoteAsset,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<CosmosMsg, ContractError> {
+    match vote_token {
+        VoteAsset::Cw20(token) => Ok(cw20_msg(
+            token,
+            &Cw20ExecuteMsg::Mint {
+                recipient: recipient.to_string(),
+                amount,
+            },
+        )?),
+        #[cfg(feature = "token-factory")]
+        VoteAsset::Native(denom) => Ok(CosmosMsg::Custom(TokenFactoryMsg::Mint {
+            denom: denom.clone(),
+            amount,
+            mint_to_address: recipient.to_string(),
+        })),
+        #[cfg(not(feature = "token-factory"))]
+        VoteAsset::Native(denom) => Err(ContractError::VoteTokenNotMintable {
+            denom: denom.clone(),
+        }),
+    }
+}
+
+#[cfg_attr(not(feature = "token-factory"), allow(unused_variables))]
+fn burn(vote_token: &VoteAsset, env: &Env, amount: Uint128) -> Result<CosmosMsg, ContractError> {
+    match vote_token {
+        VoteAsset::Cw20(token) => Ok(cw20_msg(token, &Cw20ExecuteMsg::Burn { amount })?),
+        #[cfg(feature = "token-factory")]
+        VoteAsset::Native(denom) => Ok(CosmosMsg::Custom(TokenFactoryMsg::Burn {
+            denom: denom.clone(),
+            amount,
+            burn_from_address: env.contract.address.to_string(),
+        })),
+        #[cfg(not(feature = "token-factory"))]
+        VoteAsset::Native(denom) => Err(ContractError::VoteTokenNotMintable {
+            denom: denom.clone(),
+        }),
+    }
+}
+
+fn cw20_msg(token: &Addr, msg: &Cw20ExecuteMsg) -> StdResult<CosmosMsg> {
+    let msg = WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_json_binary(msg)?,
+        funds: vec![],
+    };
+    Ok(msg.into())
+}
@@ -1,5 +1,5 @@
-use cosmwasm_std::StdError;
-use cw_utils::PaymentError;
+use cosmwasm_std::{Coin, StdError, Uint128};
+use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -16,6 +16,48 @@ pub enum ContractError {
     #[error("Expected balance vote tokens")]
     ExpectedBalanceVoteTokens,
 
-    #[error("Expected joining fees in atoms")]
-    ExpectedJoiningFeeInAtoms,
+    #[error("Joining fees in {denom} are not accepted")]
+    JoiningFeeDenomNotAccepted { denom: String },
+
+    #[error("Joining fee of {proposal} is already distributed")]
+    JoiningFeeAlreadyDistributed { proposal: String },
+
+    #[error("Joining fee of {proposal} has no yes votes to be shared among")]
+    JoiningFeeWithoutYesVotes { proposal: String },
+
+    #[error("Insufficient rewards, {available} available")]
+    InsufficientRewards { available: Coin },
+
+    #[error("Insufficient vote token supply, {available} available")]
+    InsufficientVoteTokenSupply { available: Uint128 },
+
+    #[error("Insufficient voting power, {power} available")]
+    InsufficientVotingPower { power: Uint128 },
+
+    #[error("Native {denom} vote tokens are only minted and burned through the token factory")]
+    VoteTokenNotMintable { denom: String },
+
+    #[error("Cannot burn less than the {paid} vote tokens paid")]
+    BurnAmountBelowPaid { paid: Uint128 },
+
+    #[error("Only {delegated} vote tokens are delegated")]
+    InsufficientDelegation { delegated: Uint128 },
+
+    #[error("Unrecognized reply id {0}")]
+    UnrecognizedReplyId(u64),
+
+    #[error("Missing expected data")]
+    DataMissing,
+
+    #[error("{0}")]
+    ParseError(#[from] ParseReplyError),
+
+    #[error("{0}")]
+    Semver(#[from] semver::Error),
+
+    #[error("Cannot migrate from contract {name}")]
+    MigrationInvalidContract { name: String },
+
+    #[error("Cannot migrate from newer version {version}")]
+    MigrationDowngrade { version: String },
 }
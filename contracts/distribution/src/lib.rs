@@ -1,9 +1,10 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use contract::Response;
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, StdResult};
 use error::ContractError;
-use msg::{ExecMsg, InstantiateMsg, QueryMsg};
+use msg::{ExecMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 mod contract;
 pub mod error;
@@ -23,6 +24,11 @@ pub fn instantiate(
     contract::instantiate(deps, env, info, msg)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    contract::migrate(deps, env, msg)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -33,6 +39,11 @@ pub fn execute(
     contract::execute(deps, env, info, msg)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
+    contract::reply(deps, env, reply)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     contract::query(deps, env, msg)
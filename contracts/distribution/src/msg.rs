@@ -1,24 +1,111 @@
-use common::msg::WithdrawableResp;
-use std::collections::HashMap;
+use common::{
+    msg::{ProxyMemberData, VoterTokens, WithdrawableResp},
+    vote_token::VoteToken,
+};
+use cw20::Cw20ReceiveMsg;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Coin};
+use cosmwasm_std::{Binary, Coin, Uint128};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub vote_token_price: Coin,
-    pub total_vote_tokens_in_circulation: Coin,
+    pub owner_addr: String,
+    pub reward_denoms: Vec<String>,
+    pub vote_token: VoteToken,
     pub data: Binary,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecMsg {
+    /// called by a joined proposal with the joining fee, its yes voters share it by their
+    /// reward shares
     DistributeJoiningFee {
         total_vote_tokens: Coin,
-        voter_tokens: HashMap<String, Coin>,
     },
-    BuyVoteTokens {},
-    Withdraw {},
+    BuyVoteTokens {
+        amount: Option<Uint128>,
+    },
+    /// withdraws `amounts`, or the whole balance of every reward denom when omitted
+    Withdraw {
+        amounts: Option<Vec<Coin>>,
+    },
+    /// cw20 vote tokens sent along with any of the messages
+    Receive(Cw20ReceiveMsg),
+    // this is called by membership contract, voting power goes to the `holder`, the recipient
+    // when omitted
+    MintVoteTokens {
+        recipient: String,
+        amount: Uint128,
+        holder: Option<String>,
+    },
+    // this is called by membership contract, voting power of the `holder` goes with the burn
+    BurnVoteTokens {
+        amount: Uint128,
+        holder: Option<String>,
+    },
+    // this is called by membership contract, the voter's voting power is escrowed too
+    LockVoteTokens {
+        voter: String,
+        amount: Uint128,
+    },
+    // this is called by membership contract, `refunds` give the voters their voting power back
+    UnlockVoteTokens {
+        amount: Uint128,
+        refunds: Vec<VoterTokens>,
+    },
+    // this is called by membership contract, delegators of the voter at the proposal start
+    // `height` get their part of the shares
+    AddRewardShares {
+        proposal: String,
+        voter_proxy: String,
+        amount: Uint128,
+        height: u64,
+    },
+    // this is called by membership contract, for yes votes retracted from a proposal
+    RemoveRewardShares {
+        proposal: String,
+        voter_proxy: String,
+        amount: Uint128,
+    },
+    // this is called by membership contract, for a member proposal closed without being joined,
+    // shares in it are dropped as their holders settle
+    DropRewardShares {
+        proposal: String,
+    },
+    // this is called by membership contract, for a member who left, its shares in proposals not
+    // joined yet are dropped and the other yes voters share their joining fee
+    DropMemberShares {
+        voter_proxy: String,
+    },
+    // this is called by membership contract, once the delegate proxy holds the delegated vote
+    // tokens their voting power goes to the delegate
+    DelegateVoteTokens {
+        delegator: ProxyMemberData,
+        delegate: ProxyMemberData,
+        amount: Uint128,
+    },
+    // this is called by membership contract, once the delegate proxy handed `amount` back the
+    // delegator gets its voting power back
+    UndelegateVoteTokens {
+        delegator: ProxyMemberData,
+        delegate: ProxyMemberData,
+        amount: Uint128,
+    },
+    // this is called by membership contract, for delegated vote tokens spent in a proposal or
+    // not handed back because the delegator or the delegate left, no voting power moves
+    ReleaseDelegation {
+        delegator_proxy: String,
+        delegate_proxy: String,
+        amount: Uint128,
+    },
+    // this is called by membership contract
+    UpdateConfig {
+        vote_token_price: Coin,
+    },
 }
 
 #[cw_serde]
@@ -26,4 +113,49 @@ pub enum ExecMsg {
 pub enum QueryMsg {
     #[returns(WithdrawableResp)]
     Withdrawable { proxy: String },
+    #[returns(SupplyResp)]
+    Supply {},
+    /// vote tokens the member holds outside of proposals at the beginning of `height`,
+    /// the latest when omitted
+    #[returns(VotingPowerResp)]
+    VotingPower {
+        address: String,
+        height: Option<u64>,
+    },
+    /// vote tokens in circulation at the beginning of `height`, the latest when omitted. Unlike
+    /// `VotingPower` it counts vote tokens escrowed in proposals, as proposals tally against the
+    /// whole circulation, so member powers add up to less than it while escrows are open.
+    #[returns(VotingPowerResp)]
+    TotalVotingPower { height: Option<u64> },
+    /// vote tokens the member proxy delegated and those delegated to it
+    #[returns(DelegationsResp)]
+    Delegations { proxy: String },
+}
+
+#[cw_serde]
+pub struct VotingPowerResp {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct SupplyResp {
+    /// vote tokens held by members or locked in proposals
+    pub circulating: Coin,
+    /// part of `circulating` escrowed in proposals
+    pub locked: Coin,
+}
+
+#[cw_serde]
+pub struct DelegationResp {
+    pub proxy: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct DelegationsResp {
+    /// delegate proxies holding vote tokens of the member
+    pub delegated: Vec<DelegationResp>,
+    /// delegator proxies whose vote tokens the member holds
+    pub received: Vec<DelegationResp>,
 }
@@ -1,15 +1,65 @@
-use cosmwasm_std::{Addr, Coin};
-use cw_multi_test::{App, ContractWrapper};
+use anyhow::Result as AnyResult;
+use common::vote_token::VoteToken;
+use cosmwasm_std::{Addr, Binary, Coin, Uint128};
+use cw_multi_test::{AppResponse, ContractWrapper, Executor};
 
-use crate::{execute, instantiate, query, state::TOTAL_VOTE_TOKENS_IN_CIRCULATION};
+use crate::{
+    execute, instantiate, migrate,
+    msg::{ExecMsg, InstantiateMsg, QueryMsg, SupplyResp},
+    query, reply,
+    state::{Config, CONFIG},
+};
+
+#[cfg(test)]
+mod tests;
+
+pub use common::multitest::App;
+
+/// Native vote tokens are minted through the token factory rather than paid out of a reserve
+/// prefunded by membership
+pub const TOKEN_FACTORY: bool = cfg!(feature = "token-factory");
 
 pub struct CodeId(u64);
 
 impl CodeId {
     pub fn store_code(app: &mut App) -> Self {
-        let contract = ContractWrapper::new(execute, instantiate, query);
+        #[cfg(feature = "token-factory")]
+        let contract = ContractWrapper::new(execute, instantiate, query)
+            .with_reply(reply)
+            .with_migrate(migrate);
+        #[cfg(not(feature = "token-factory"))]
+        let contract = ContractWrapper::new_with_empty(execute, instantiate, query)
+            .with_reply_empty(reply)
+            .with_migrate_empty(migrate);
         CodeId(app.store_code(Box::new(contract)))
     }
+
+    #[allow(clippy::too_many_arguments)]
+    #[track_caller]
+    pub fn instantiate(
+        self,
+        app: &mut App,
+        sender: &Addr,
+        vote_token_price: Coin,
+        owner_addr: &Addr,
+        reward_denoms: &[&str],
+        vote_token: VoteToken,
+        label: &str,
+    ) -> AnyResult<Contract> {
+        let msg = InstantiateMsg {
+            vote_token_price,
+            owner_addr: owner_addr.to_string(),
+            reward_denoms: reward_denoms
+                .iter()
+                .map(|denom| denom.to_string())
+                .collect(),
+            vote_token,
+            data: Binary::default(),
+        };
+
+        app.instantiate_contract(self.0, sender.clone(), &msg, &[], label, None)
+            .map(Contract)
+    }
 }
 
 impl From<CodeId> for u64 {
@@ -29,10 +79,77 @@ impl Contract {
     pub fn addr(&self) -> &Addr {
         &self.0
     }
+    #[track_caller]
+    pub fn mint_vote_tokens(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        recipient: &Addr,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::MintVoteTokens {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+            holder: None,
+        };
+        app.execute_contract(sender.clone(), self.0.clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn lock_vote_tokens(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        voter: &Addr,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::LockVoteTokens {
+            voter: voter.to_string(),
+            amount: Uint128::new(amount),
+        };
+        app.execute_contract(sender.clone(), self.0.clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn burn_vote_tokens(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        amount: u128,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::BurnVoteTokens {
+            amount: Uint128::new(amount),
+            holder: None,
+        };
+        app.execute_contract(sender.clone(), self.0.clone(), &msg, funds)
+    }
+
+    pub fn distribute_joining_fee(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        total_vote_tokens: Coin,
+        fee: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::DistributeJoiningFee { total_vote_tokens };
+        app.execute_contract(sender.clone(), self.0.clone(), &msg, fee)
+    }
+
     #[track_caller]
     pub fn total_vote_tokens_in_circulation(&self, app: &App) -> Coin {
-        TOTAL_VOTE_TOKENS_IN_CIRCULATION
-            .query(&app.wrap(), self.addr().clone())
+        self.supply(app).circulating
+    }
+
+    #[track_caller]
+    pub fn supply(&self, app: &App) -> SupplyResp {
+        app.wrap()
+            .query_wasm_smart(self.addr(), &QueryMsg::Supply {})
             .unwrap()
     }
+
+    #[track_caller]
+    pub fn load_config(&self, app: &App) -> Config {
+        CONFIG.query(&app.wrap(), self.addr().clone()).unwrap()
+    }
 }
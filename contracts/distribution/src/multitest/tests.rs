@@ -0,0 +1,228 @@
+use common::msg::membership::{IsProposalResp, QueryMsg as MembershipQueryMsg};
+use common::msg::ProxyMemberData;
+use common::{
+    keys::{ATOM, VOTE_DENOM},
+    multitest::TokenFactory,
+    vote_token::VoteToken,
+};
+use cosmwasm_std::{
+    coin, coins, to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult,
+};
+use cw_multi_test::{ContractWrapper, Executor};
+
+use super::{App, CodeId, Contract, TOKEN_FACTORY};
+use crate::error::ContractError;
+use crate::msg::ExecMsg;
+
+const PROPOSAL: &str = "proposal";
+
+/// Membership standing in for the real one, `PROPOSAL` is the only proposal it instantiated
+fn membership_contract(app: &mut App) -> Addr {
+    let instantiate = |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+        Ok(Response::new())
+    };
+    let execute = |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+        Ok(Response::new())
+    };
+    let query = |_: Deps, _: Env, msg: MembershipQueryMsg| -> StdResult<Binary> {
+        match msg {
+            MembershipQueryMsg::IsProposal { addr } => to_json_binary(&IsProposalResp {
+                ok: addr == PROPOSAL,
+            }),
+            _ => Err(StdError::generic_err("not supported by the mock")),
+        }
+    };
+
+    let code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        execute,
+        instantiate,
+        query,
+    )));
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked("admin"),
+        &Empty {},
+        &[],
+        "Membership",
+        None,
+    )
+    .unwrap()
+}
+
+/// Distribution instantiated by the mock membership, `PROPOSAL` and alice hold 60 ATOM each and
+/// the admin can fund a `VOTE` reserve
+fn mocked_distribution() -> (App, Contract) {
+    let mut app = TokenFactory::app_with_balances([
+        (Addr::unchecked(PROPOSAL), coins(60, ATOM)),
+        (Addr::unchecked("alice"), coins(60, ATOM)),
+        (Addr::unchecked("admin"), coins(100, VOTE_DENOM)),
+    ]);
+    let membership = membership_contract(&mut app);
+
+    let distribution = CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            &membership,
+            coin(5, ATOM),
+            &Addr::unchecked("admin"),
+            &[ATOM],
+            VoteToken::Native {},
+            "Distribution",
+        )
+        .unwrap();
+
+    (app, distribution)
+}
+
+#[test]
+fn joining_fee_paid_by_membership_proposals_only() {
+    let proposal = Addr::unchecked(PROPOSAL);
+    let alice = Addr::unchecked("alice");
+
+    let (mut app, distribution) = mocked_distribution();
+    let yes_votes = distribution
+        .load_config(&app)
+        .vote_token
+        .coin(10u128.into());
+
+    let err = distribution
+        .distribute_joining_fee(&mut app, &alice, yes_votes.clone(), &coins(30, ATOM))
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::Unauthorized
+    ));
+
+    distribution
+        .distribute_joining_fee(&mut app, &proposal, yes_votes, &coins(30, ATOM))
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_balance(distribution.addr(), ATOM).unwrap(),
+        coin(30, ATOM)
+    );
+}
+
+#[test]
+fn joining_fee_without_yes_votes_rejected() {
+    let proposal = Addr::unchecked(PROPOSAL);
+
+    let (mut app, distribution) = mocked_distribution();
+    let no_votes = distribution.load_config(&app).vote_token.coin(0u128.into());
+
+    let err = distribution
+        .distribute_joining_fee(&mut app, &proposal, no_votes, &coins(30, ATOM))
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::JoiningFeeWithoutYesVotes { proposal } if proposal == PROPOSAL
+    ));
+}
+
+#[test]
+fn vote_tokens_without_voting_power_neither_escrowed_nor_delegated() {
+    let admin = Addr::unchecked("admin");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+
+    let (mut app, distribution) = mocked_distribution();
+    let membership = distribution.load_config(&app).membership_contract;
+    if !TOKEN_FACTORY {
+        app.send_tokens(admin, distribution.addr().clone(), &coins(100, VOTE_DENOM))
+            .unwrap();
+    }
+
+    // alice gets 10 vote tokens with their voting power, more of them than that have none
+    distribution
+        .mint_vote_tokens(&mut app, &membership, &alice, 10)
+        .unwrap();
+
+    let err = distribution
+        .lock_vote_tokens(&mut app, &membership, &alice, 15)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::InsufficientVotingPower { power } if power.u128() == 10
+    ));
+
+    distribution
+        .lock_vote_tokens(&mut app, &membership, &alice, 4)
+        .unwrap();
+
+    let msg = ExecMsg::DelegateVoteTokens {
+        delegator: ProxyMemberData {
+            owner_addr: alice.to_string(),
+            proxy_addr: alice.to_string(),
+        },
+        delegate: ProxyMemberData {
+            owner_addr: bob.to_string(),
+            proxy_addr: bob.to_string(),
+        },
+        amount: 7u128.into(),
+    };
+    let err = app
+        .execute_contract(membership, distribution.addr().clone(), &msg, &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::InsufficientVotingPower { power } if power.u128() == 6
+    ));
+}
+
+#[cfg(feature = "token-factory")]
+#[test]
+fn factory_denom_is_minted_and_burned_by_distribution() {
+    let membership = Addr::unchecked("membership");
+    let admin = Addr::unchecked("admin");
+    let alice = Addr::unchecked("alice");
+
+    let mut app = TokenFactory::app();
+
+    let distribution = CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            &membership,
+            coin(5, ATOM),
+            &admin,
+            &[ATOM],
+            VoteToken::Native {},
+            "Distribution",
+        )
+        .unwrap();
+
+    let denom = distribution.load_config(&app).vote_token.denom();
+    assert_eq!(denom, format!("factory/{}/vote", distribution.addr()));
+
+    distribution
+        .mint_vote_tokens(&mut app, &membership, &alice, 10)
+        .unwrap();
+
+    assert_eq!(
+        app.wrap().query_balance(&alice, &denom).unwrap(),
+        coin(10, &denom)
+    );
+    assert_eq!(distribution.supply(&app).circulating, coin(10, &denom));
+
+    let err = distribution
+        .mint_vote_tokens(&mut app, &alice, &alice, 10)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::Unauthorized
+    ));
+
+    // alice hands back 4 vote tokens, they are burned rather than kept in a reserve
+    app.send_tokens(alice.clone(), membership.clone(), &coins(4, &denom))
+        .unwrap();
+    distribution
+        .burn_vote_tokens(&mut app, &membership, 4, &coins(4, &denom))
+        .unwrap();
+
+    assert_eq!(
+        app.wrap()
+            .query_balance(distribution.addr(), &denom)
+            .unwrap(),
+        coin(0, &denom)
+    );
+    assert_eq!(distribution.supply(&app).circulating, coin(6, &denom));
+}
@@ -1,6 +1,7 @@
+use common::vote_token::VoteAsset;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Coin, Empty, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
 
 #[cw_serde]
 #[derive(Default)]
@@ -11,26 +12,77 @@ pub struct Correction {
 #[cw_serde]
 #[derive(Default)]
 pub struct MemberData {
-    pub reward_balance: Coin,
+    pub reward_balance: Uint128,
     pub points_balance: Uint128,
 }
 
 impl MemberData {
-    pub fn with_reward_balance(mut self, bal: Coin) -> Self {
-        self.reward_balance = bal;
-        self
+    /// Moves whole leftover points into the reward balance
+    pub fn settle(&mut self, points_scale: u128) {
+        self.reward_balance += self.points_balance / Uint128::new(points_scale);
+        self.points_balance %= Uint128::new(points_scale);
     }
 }
 
+#[cw_serde]
+pub struct ProposalReward {
+    pub denom: String,
+    /// joining fee points for each yes vote token
+    pub points_per_share: Uint128,
+}
+
 #[cw_serde]
 pub struct Config {
     pub membership_contract: Addr,
     pub vote_token_price: Coin,
+    pub owner_addr: Addr,
+    /// denoms joining fees can be paid in, the vote token price is in one of them
+    pub reward_denoms: Vec<String>,
+    pub vote_token: VoteAsset,
+    /// distribution mints and burns vote tokens instead of keeping a reserve of them
+    pub vote_token_minter: bool,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const TOTAL_VOTE_TOKENS_IN_CIRCULATION: Item<Coin> =
-    Item::new("total_vote_tokens_in_circulation");
+pub const TOTAL_VOTE_TOKENS_IN_CIRCULATION: SnapshotItem<Coin> = SnapshotItem::new(
+    "total_vote_tokens_in_circulation",
+    "total_vote_tokens_in_circulation__checkpoints",
+    "total_vote_tokens_in_circulation__changelog",
+    Strategy::EveryBlock,
+);
+pub const LOCKED_VOTE_TOKENS: Item<Coin> = Item::new("locked_vote_tokens");
+
+// member => vote tokens it holds outside of proposals
+pub const VOTING_POWER: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "voting_power",
+    "voting_power__checkpoints",
+    "voting_power__changelog",
+    Strategy::EveryBlock,
+);
+
+// reward denom => correction
+pub const CORRECTION: Map<&str, Correction> = Map::new("correction");
+// (proxy, reward denom) => member data
+pub const MEMBER_DATA: Map<(&Addr, &str), MemberData> = Map::new("member_data");
+// joined proposal => joining fee reward of its yes voters
+pub const PROPOSAL_REWARDS: Map<&Addr, ProposalReward> = Map::new("proposal_rewards");
+// (proxy, proposal) => yes vote tokens not settled yet
+pub const REWARD_SHARES: Map<(&Addr, &Addr), Uint128> = Map::new("reward_shares");
+// proposal closed without being joined, shares in it settle to nothing
+pub const CLOSED_PROPOSALS: Map<&Addr, Empty> = Map::new("closed_proposals");
+// proposal not joined yet => yes vote tokens of members who left, they share none of its fee
+pub const DROPPED_SHARES: Map<&Addr, Uint128> = Map::new("dropped_shares");
 
-pub const CORRECTION: Item<Correction> = Item::new("correction");
-pub const MEMBER_DATA: Map<&Addr, MemberData> = Map::new("member_data");
+// (delegate proxy, delegator proxy) => vote tokens delegated, kept at zero once undelegated as
+// delegations at a proposal start height weigh the delegate's reward shares
+pub const DELEGATIONS: SnapshotMap<(&Addr, &Addr), Uint128> = SnapshotMap::new(
+    "delegations",
+    "delegations__checkpoints",
+    "delegations__changelog",
+    Strategy::EveryBlock,
+);
+// (delegator proxy, delegate proxy) of delegations not undelegated yet
+pub const DELEGATES: Map<(&Addr, &Addr), Empty> = Map::new("delegates");
+// (delegator proxy, proposal, delegate proxy) => yes vote tokens of the delegate credited to the
+// delegator, not settled yet
+pub const DELEGATED_SHARES: Map<(&Addr, &Addr, &Addr), Uint128> = Map::new("delegated_shares");
@@ -1,18 +1,25 @@
-use common::keys::{ATOM, VOTE_DENOM};
+use common::{
+    keys::VOTE_DENOM,
+    vote_token::{VoteAsset, VoteToken},
+};
 use cosmwasm_std::{
-    coin, ensure, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo, Reply,
-    Response, StdResult, SubMsg, Uint128, WasmMsg,
+    ensure, from_json, to_json_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Reply, Response, StdResult, SubMsg, Uint128, WasmMsg,
 };
-use cw2::set_contract_version;
-use cw_utils::must_pay;
+use cw2::{get_contract_version, set_contract_version};
+use cw_utils::{may_pay, nonpayable, Duration};
+use semver::Version;
 
 use crate::{
     error::ContractError,
-    msg::InstantiateMsg,
-    state::{Config, CONFIG},
+    msg::{InstantiateMsg, MigrateMsg},
+    state::{Config, CONFIG, DELEGATED_ESCROW, ESCROWED_DELEGATIONS},
 };
 use common::msg::membership::{ExecMsg, QueryMsg};
-use distribution::msg::InstantiateMsg as DistributionInstantiateMsg;
+use distribution::msg::{
+    DelegationResp, DelegationsResp, ExecMsg as DistributionExecMsg,
+    InstantiateMsg as DistributionInstantiateMsg, QueryMsg as DistributionQueryMsg,
+};
 
 mod exec;
 mod query;
@@ -23,6 +30,9 @@ const DISTRIBUTION_INSTANTIATION_REPLY_ID: u64 = 2;
 const PROPOSAL_INSTANTIATION_REPLY_ID: u64 = 3;
 const PROPOSAL_PASS_REPLY_ID: u64 = 4;
 const PROXY_INSTANTIATION_REPLY_ID: u64 = 5;
+const GENERIC_PROPOSAL_INSTANTIATION_REPLY_ID: u64 = 6;
+const REMOVAL_PROPOSAL_INSTANTIATION_REPLY_ID: u64 = 7;
+const GENERIC_PROPOSAL_MSG_REPLY_ID: u64 = 8;
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -40,54 +50,63 @@ pub fn instantiate(
         ContractError::NotEnoughInitialMembers
     );
 
-    let mut vote_funds = must_pay(&info, VOTE_DENOM)?;
-
-    ensure!(
-        msg.initial_vote_token_distribution_part >= Decimal::percent(0)
-            && msg.initial_vote_token_distribution_part <= Decimal::percent(100),
-        ContractError::InitialisationInvalidVoteTokenDistributionPart
-    );
-
-    if msg.initial_vote_token_distribution_part > Decimal::percent(0) {
-        vote_funds = vote_funds * msg.initial_vote_token_distribution_part;
-    }
-
-    ensure!(
-        vote_funds / Uint128::new(msg.initial_members.len() as u128) >= Uint128::new(1),
-        ContractError::InitialisationLessVoteTokens
-    );
-
-    ensure!(
-        msg.joining_fee.denom == ATOM,
-        ContractError::JoiningFeeDenomInvalid {
-            denom: ATOM.to_string()
-        }
-    );
-
     let config = Config {
         proposal_code_id: msg.proposal_code_id,
         proxy_code_id: msg.proxy_code_id,
         distribution_contract: Addr::unchecked(""), // will get it in reply!
+        reward_denoms: msg.reward_denoms.clone(),
         joining_fee: msg.joining_fee,
+        initial_votes: msg.initial_votes,
         initial_vote_token_distribution_part: msg.initial_vote_token_distribution_part,
+        voting_period: msg.voting_period,
+        quorum: msg.quorum,
+        threshold: msg.threshold,
+        // distribution tells the actual one in reply!
+        vote_token: VoteAsset::Native(VOTE_DENOM.to_owned()),
     };
 
+    validate_config(&config)?;
+
+    validate_vote_token_price(&config, &msg.vote_token_price)?;
+
+    // a prefunded VOTE pool is split among initial members, without one each of them gets
+    // `initial_votes` minted, unless the pool of a reserve held token is funded later on
+    let vote_funds = match msg.vote_token {
+        VoteToken::Native {} => may_pay(&info, &config.vote_token.denom())?,
+        VoteToken::Cw20(_) => {
+            nonpayable(&info)?;
+            Uint128::zero()
+        }
+    };
+
+    let vote_tokens_per_member = if vote_funds.is_zero() {
+        msg.initial_votes
+    } else {
+        pool_share(&config, vote_funds, msg.initial_members.len())
+    };
+
+    ensure!(
+        vote_tokens_per_member >= Uint128::new(1),
+        ContractError::InitialisationLessVoteTokens
+    );
+
     CONFIG.save(deps.storage, &config)?;
-    
 
-    let members_data = to_binary(&msg.initial_members)?;
+    let members_data = to_json_binary(&msg.initial_members)?;
     let membership_contract = env.contract.address.to_string();
 
     let instantiate_msg = DistributionInstantiateMsg {
         vote_token_price: msg.vote_token_price,
-        total_vote_tokens_in_circulation: coin(vote_funds.u128(), VOTE_DENOM),
+        owner_addr: deps.api.addr_validate(&msg.owner_addr)?.into_string(),
+        reward_denoms: msg.reward_denoms,
+        vote_token: msg.vote_token,
         data: members_data,
     };
 
     let instantiate_msg = WasmMsg::Instantiate {
         admin: Some(membership_contract),
         code_id: msg.distribution_code_id,
-        msg: to_binary(&instantiate_msg)?,
+        msg: to_json_binary(&instantiate_msg)?,
         funds: vec![],
         label: "Distribution".to_owned(),
     };
@@ -100,6 +119,155 @@ pub fn instantiate(
     Ok(resp)
 }
 
+// rules shared by instantiation and config updates
+fn validate_config(config: &Config) -> Result<(), ContractError> {
+    ensure!(
+        config.initial_vote_token_distribution_part >= Decimal::percent(0)
+            && config.initial_vote_token_distribution_part <= Decimal::percent(100),
+        ContractError::InitialisationInvalidVoteTokenDistributionPart
+    );
+
+    ensure!(
+        config.voting_period != Duration::Height(0) && config.voting_period != Duration::Time(0),
+        ContractError::InitialisationInvalidVotingPeriod
+    );
+
+    ensure!(
+        config.quorum > Decimal::percent(0) && config.quorum <= Decimal::percent(100),
+        ContractError::InitialisationInvalidQuorum
+    );
+
+    ensure!(
+        config.threshold < Decimal::percent(100),
+        ContractError::InitialisationInvalidThreshold
+    );
+
+    ensure!(
+        !config.joining_fee.is_empty()
+            && config
+                .joining_fee
+                .iter()
+                .all(|fee| config.reward_denoms.contains(&fee.denom)),
+        ContractError::JoiningFeeDenomInvalid {
+            denom: config.reward_denoms.join(", ")
+        }
+    );
+
+    Ok(())
+}
+
+fn validate_vote_token_price(config: &Config, price: &Coin) -> Result<(), ContractError> {
+    ensure!(
+        config.reward_denoms.contains(&price.denom) && !price.amount.is_zero(),
+        ContractError::VoteTokenPriceInvalid {
+            denom: config.reward_denoms.join(", ")
+        }
+    );
+
+    Ok(())
+}
+
+// share of each initial member in a vote token pool, the rest stays in the distribution reserve
+fn pool_share(config: &Config, pool: Uint128, members: usize) -> Uint128 {
+    let mut distributed = pool;
+
+    if config.initial_vote_token_distribution_part > Decimal::percent(0) {
+        distributed = distributed * config.initial_vote_token_distribution_part
+    }
+
+    distributed / Uint128::new(members as u128)
+}
+
+// vote token supply changes go through distribution
+fn distribution_msg(
+    config: &Config,
+    msg: &DistributionExecMsg,
+    funds: Vec<Coin>,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: config.distribution_contract.to_string(),
+        msg: to_json_binary(msg)?,
+        funds,
+    })
+}
+
+// delegations are recorded by distribution between member proxies
+fn received_delegations(
+    deps: Deps,
+    config: &Config,
+    proxy: &Addr,
+) -> StdResult<Vec<DelegationResp>> {
+    let delegations: DelegationsResp = deps.querier.query_wasm_smart(
+        &config.distribution_contract,
+        &DistributionQueryMsg::Delegations {
+            proxy: proxy.to_string(),
+        },
+    )?;
+
+    Ok(delegations.received)
+}
+
+/// Records the delegated vote tokens among those the voter proxy just escrowed in the proposal.
+/// Its own vote tokens are escrowed first, so delegated ones are escrowed as far as the proxy
+/// does not hold the delegations which are not escrowed yet.
+fn escrow_delegations(
+    deps: DepsMut,
+    config: &Config,
+    proposal: &Addr,
+    voter_proxy: &Addr,
+) -> StdResult<()> {
+    let delegated: Uint128 = received_delegations(deps.as_ref(), config, voter_proxy)?
+        .iter()
+        .map(|delegation| delegation.amount)
+        .sum();
+    let escrowed = ESCROWED_DELEGATIONS
+        .may_load(deps.storage, voter_proxy)?
+        .unwrap_or_default();
+    let held = config.vote_token.balance(&deps.querier, voter_proxy)?;
+
+    let escrow = delegated.saturating_sub(escrowed).saturating_sub(held);
+    if escrow.is_zero() {
+        return Ok(());
+    }
+
+    DELEGATED_ESCROW.update(
+        deps.storage,
+        (proposal, voter_proxy),
+        |delegated| -> StdResult<_> { Ok(delegated.unwrap_or_default() + escrow) },
+    )?;
+    ESCROWED_DELEGATIONS.save(deps.storage, voter_proxy, &(escrowed + escrow))
+}
+
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    ensure!(
+        stored.contract == CONTRACT_NAME,
+        ContractError::MigrationInvalidContract {
+            name: stored.contract
+        }
+    );
+
+    let stored_version: Version = stored.version.parse()?;
+    let version: Version = CONTRACT_VERSION.parse()?;
+
+    ensure!(
+        stored_version <= version,
+        ContractError::MigrationDowngrade {
+            version: stored.version
+        }
+    );
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION);
+
+    Ok(resp)
+}
+
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -109,13 +277,64 @@ pub fn execute(
     use ExecMsg::*;
 
     match msg {
+        Receive(wrapper) => {
+            let config = CONFIG.load(deps.storage)?;
+            let (info, msg) = config
+                .vote_token
+                .receive(&info, wrapper)
+                .ok_or(ContractError::Unauthorized)?;
+            execute(deps, env, info, from_json(msg)?)
+        }
         // this is called by proxy contract
         ProposeMember { addr } => exec::propose_member(deps, env, info, addr),
+        // this is called by proxy contract
+        ProposeGeneric {
+            title,
+            description,
+            msgs,
+        } => exec::propose_generic(deps, env, info, title, description, msgs),
+        // this is called by proxy contract
+        ProposeRemoval { owner } => exec::propose_removal(deps, env, info, owner),
         // this is called by proposal contract
-        VoteMemberProposal { voter, voter_proxy } => {
-            exec::vote_member_proposal(deps, env, info, voter, voter_proxy)
-        }
+        VoteMemberProposal {
+            voter_proxy,
+            amount,
+        } => exec::vote_member_proposal(deps, env, info, voter_proxy, amount),
+        // this is called by proposal contract
+        VoteGenericProposal {
+            voter_proxy,
+            amount,
+        } => exec::vote_generic_proposal(deps, env, info, voter_proxy, amount),
+        // this is called by proposal contract
+        VoteRemovalProposal {
+            voter_proxy,
+            amount,
+        } => exec::vote_removal_proposal(deps, env, info, voter_proxy, amount),
         NewMember {} => exec::new_member(deps, env, info),
+        // this is called by the admin
+        FundPool {} => exec::fund_pool(deps, env, info),
+        // this is called by proxy contract
+        MemberLeft {} => exec::member_left(deps, info),
+        // this is called by proxy contract
+        Delegate { to } => exec::delegate(deps, info, to),
+        // this is called by proxy contract
+        Undelegate { from, amount } => exec::undelegate(deps, info, from, amount),
+        // this is called by proposal contract
+        VoteRetracted {
+            voter_proxy,
+            amount,
+            yes,
+        } => exec::vote_retracted(deps, info, voter_proxy, amount, yes),
+        // this is called by proposal contract
+        ProposalClosed { refunds } => exec::proposal_closed(deps, info, refunds),
+        MigrateChildren {
+            code_id,
+            kind,
+            start_after,
+            limit,
+        } => exec::migrate_children(deps, env, info, code_id, kind, start_after, limit),
+        // this is called by membership itself through a generic proposal, or by its admin
+        UpdateConfig { config } => exec::update_config(deps, env, info, config),
     }
 }
 
@@ -125,23 +344,54 @@ pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, Contract
             reply::distribution_instantiated(deps, env, reply.result.into_result())
         }
         INITIAL_PROXY_INSTANTIATION_REPLY_ID => {
-            reply::initial_proxy_instantiated(deps, reply.result.into_result())
+            reply::initial_proxy_instantiated(deps, env, reply.result.into_result())
         }
         PROPOSAL_INSTANTIATION_REPLY_ID => {
             reply::proposal_instantiated(deps, reply.result.into_result())
         }
+        GENERIC_PROPOSAL_INSTANTIATION_REPLY_ID => {
+            reply::generic_proposal_instantiated(deps, reply.result.into_result())
+        }
+        REMOVAL_PROPOSAL_INSTANTIATION_REPLY_ID => {
+            reply::removal_proposal_instantiated(deps, reply.result.into_result())
+        }
         PROPOSAL_PASS_REPLY_ID => reply::proposal_passed(deps, env, reply.result.into_result()),
-        PROXY_INSTANTIATION_REPLY_ID => reply::proxy_instantiated(deps, reply.result.into_result()),
+        PROXY_INSTANTIATION_REPLY_ID => {
+            reply::proxy_instantiated(deps, env, reply.result.into_result())
+        }
+        GENERIC_PROPOSAL_MSG_REPLY_ID => {
+            reply::generic_proposal_msg_executed(deps, reply.result.into_result())
+        }
         id => Err(ContractError::UnrecognizedReplyId(id)),
     }
 }
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     use QueryMsg::*;
 
     match msg {
-        IsMember { addr } => to_binary(&query::is_member(deps, addr)?),
-        IsProposedMember { addr } => to_binary(&query::is_proposed_member(deps, addr)?),
-        OwnerProxy { owner } => to_binary(&query::owner_proxy(deps, owner)?),
+        IsMember { addr } => to_json_binary(&query::is_member(deps, addr)?),
+        IsProposedMember { addr } => to_json_binary(&query::is_proposed_member(deps, addr)?),
+        IsProposal { addr } => to_json_binary(&query::is_proposal(deps, addr)?),
+        OwnerProxy { owner } => to_json_binary(&query::owner_proxy(deps, owner)?),
+        MemberByProxy { proxy } => to_json_binary(&query::member_by_proxy(deps, proxy)?),
+        MemberProfile { owner } => to_json_binary(&query::member_profile(deps, owner)?),
+        ListMembers { start_after, limit } => {
+            to_json_binary(&query::list_members(deps, start_after, limit)?)
+        }
+        ListCandidates { start_after, limit } => {
+            to_json_binary(&query::list_candidates(deps, start_after, limit)?)
+        }
+        MemberCount {} => to_json_binary(&query::member_count(deps)?),
+        SupplyInvariant {} => to_json_binary(&query::supply_invariant(deps)?),
+        VotingPowerAtHeight { address, height } => {
+            to_json_binary(&query::voting_power_at_height(deps, address, height)?)
+        }
+        TotalPowerAtHeight { height } => {
+            to_json_binary(&query::total_power_at_height(deps, height)?)
+        }
+        Info {} => to_json_binary(&query::info(deps)?),
+        Dao {} => to_json_binary(&query::dao(env)),
+        Delegations { owner } => to_json_binary(&query::delegations(deps, owner)?),
     }
 }
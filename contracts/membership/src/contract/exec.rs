@@ -1,21 +1,45 @@
 use std::collections::HashMap;
 
-use common::keys::VOTE_DENOM;
+use common::msg::{
+    membership::{ChildKind, ConfigUpdate},
+    ProxyMemberData, VoterTokens,
+};
 use cosmwasm_std::{
-    coin, coins, ensure, to_binary, Addr, BankMsg, Coin, DepsMut, Empty, Env, MessageInfo, Order,
-    Response, SubMsg, Uint128, WasmMsg,
+    coin, coins, ensure, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Empty, Env,
+    Event, MessageInfo, Order, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 
-use cw_utils::must_pay;
-use proposal::msg::{ExecMsg as ProposalExecMsg, InstantiateMsg as ProposalInstantiateMsg};
-use proxy::msg::InstantiateMsg as ProxyInstantiateMsg;
+use cw_storage_plus::Bound;
+use cw_utils::{may_pay, must_pay};
+use distribution::msg::{
+    DelegationResp, DelegationsResp, ExecMsg as DistributionExecMsg,
+    MigrateMsg as DistributionMigrateMsg, QueryMsg as DistributionQueryMsg, VotingPowerResp,
+};
+use proposal::msg::{
+    ExecMsg as ProposalExecMsg, GenericProposalResp, InstantiateMsg as ProposalInstantiateMsg,
+    MigrateMsg as ProposalMigrateMsg, ProposalKind, QueryMsg as ProposalQueryMsg, VoteOption,
+};
+use proxy::msg::{
+    ExecMsg as ProxyExecMsg, InstantiateMsg as ProxyInstantiateMsg, MigrateMsg as ProxyMigrateMsg,
+};
 
 use crate::{
-    contract::{PROPOSAL_INSTANTIATION_REPLY_ID, PROPOSAL_PASS_REPLY_ID},
+    contract::{
+        GENERIC_PROPOSAL_INSTANTIATION_REPLY_ID, GENERIC_PROPOSAL_MSG_REPLY_ID,
+        PROPOSAL_INSTANTIATION_REPLY_ID, PROPOSAL_PASS_REPLY_ID,
+        REMOVAL_PROPOSAL_INSTANTIATION_REPLY_ID,
+    },
     error::ContractError,
-    state::{candidates, members, CONFIG},
+    state::{
+        candidates, members, removals, Config, ExecutingProposal, PendingMember, AWAITING_POOL,
+        CONFIG, DELEGATED_ESCROW, ESCROWED_DELEGATIONS, EXECUTING_PROPOSAL, GENERIC_PROPOSALS,
+        PENDING_MEMBER, PROPOSALS,
+    },
 };
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 pub fn propose_member(
     deps: DepsMut,
     env: Env,
@@ -24,7 +48,8 @@ pub fn propose_member(
 ) -> Result<Response, ContractError> {
     let addr = deps.api.addr_validate(&addr)?;
 
-    let vote_tokens = must_pay(&info, VOTE_DENOM)?;
+    let config = CONFIG.load(deps.storage)?;
+    let vote_tokens = must_pay(&info, &config.vote_token.denom())?;
 
     ensure!(
         members().has(deps.storage, &info.sender),
@@ -45,81 +70,237 @@ pub fn propose_member(
         ContractError::ExistingProposalInProgress
     );
 
+    let expiration = config.voting_period.after(&env.block);
     let membership_contract = env.contract.address.into_string();
-    let config = CONFIG.load(deps.storage)?;
 
     let inst_msg = ProposalInstantiateMsg {
         proposer: info.sender.to_string(),
-        proposed_owner: addr.to_string(),
-        distribution_contract: config.distribution_contract.into_string(),
+        distribution_contract: config.distribution_contract.to_string(),
         membership_contract: membership_contract.clone(),
-        joining_fee: config.joining_fee,
+        kind: ProposalKind::Member {
+            proposed_owner: addr.to_string(),
+            joining_fee: config.joining_fee.clone(),
+        },
+        expiration,
+        quorum: config.quorum,
+        threshold: config.threshold,
+        vote_token: config.vote_token.clone(),
+        escrow: vote_tokens,
     };
     let inst_msg = WasmMsg::Instantiate {
         admin: Some(membership_contract),
         code_id: config.proposal_code_id,
-        msg: to_binary(&inst_msg)?,
-        funds: coins(vote_tokens.u128(), VOTE_DENOM),
+        msg: to_json_binary(&inst_msg)?,
+        funds: vec![],
         label: format!("{} Proposal", addr),
     };
     let inst_msg = SubMsg::reply_on_success(inst_msg, PROPOSAL_INSTANTIATION_REPLY_ID);
 
+    let lock_msg = proposer_lock_msg(
+        deps.as_ref(),
+        &config,
+        &info.sender,
+        vote_tokens,
+        env.block.height,
+    )?;
+
     let resp = Response::new()
         .add_submessage(inst_msg)
+        .add_message(lock_msg)
         .add_attribute("action", "propose_member")
         .add_attribute("sender", info.sender.as_str())
         .add_attribute("addr", addr.as_str());
     Ok(resp)
 }
 
-pub fn vote_member_proposal(
+pub fn propose_generic(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    voter: String,
-    voter_proxy: String,
+    title: String,
+    description: String,
+    msgs: Vec<CosmosMsg>,
 ) -> Result<Response, ContractError> {
-    let voter = deps.api.addr_validate(&voter)?;
-    let voter_proxy = deps.api.addr_validate(&voter_proxy)?;
+    let config = CONFIG.load(deps.storage)?;
+    let vote_tokens = must_pay(&info, &config.vote_token.denom())?;
+
     ensure!(
         members().has(deps.storage, &info.sender),
         ContractError::Unauthorized
     );
+
+    let expiration = config.voting_period.after(&env.block);
+    let membership_contract = env.contract.address.into_string();
+
+    let inst_msg = ProposalInstantiateMsg {
+        proposer: info.sender.to_string(),
+        distribution_contract: config.distribution_contract.to_string(),
+        membership_contract: membership_contract.clone(),
+        kind: ProposalKind::Generic {
+            title: title.clone(),
+            description,
+            msgs,
+        },
+        expiration,
+        quorum: config.quorum,
+        threshold: config.threshold,
+        vote_token: config.vote_token.clone(),
+        escrow: vote_tokens,
+    };
+    let inst_msg = WasmMsg::Instantiate {
+        admin: Some(membership_contract),
+        code_id: config.proposal_code_id,
+        msg: to_json_binary(&inst_msg)?,
+        funds: vec![],
+        label: format!("{} Proposal", title),
+    };
+    let inst_msg = SubMsg::reply_on_success(inst_msg, GENERIC_PROPOSAL_INSTANTIATION_REPLY_ID);
+
+    let lock_msg = proposer_lock_msg(
+        deps.as_ref(),
+        &config,
+        &info.sender,
+        vote_tokens,
+        env.block.height,
+    )?;
+
+    let resp = Response::new()
+        .add_submessage(inst_msg)
+        .add_message(lock_msg)
+        .add_attribute("action", "propose_generic")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("title", title);
+    Ok(resp)
+}
+
+pub fn propose_removal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let vote_tokens = must_pay(&info, &config.vote_token.denom())?;
+
     ensure!(
-        !members().has(deps.storage, &info.sender),
-        ContractError::AlreadyAMember
+        members().has(deps.storage, &info.sender),
+        ContractError::Unauthorized
     );
 
     ensure!(
-        candidates()
+        members()
             .idx
-            .proposal
-            .item(deps.storage, info.sender.clone())?
+            .owner
+            .item(deps.storage, owner.clone())?
             .is_some(),
-        ContractError::NotProposedMember
+        ContractError::NotAMember
     );
 
     ensure!(
-        members().load(deps.storage, &voter_proxy)? == voter,
-        ContractError::MemberProxyMismatch
+        !removals().has(deps.storage, &owner),
+        ContractError::ExistingProposalInProgress
     );
 
-    // let owner: Addr = proposal::state::OWNER.query(&deps.querier, info.sender)?;
+    let expiration = config.voting_period.after(&env.block);
+    let membership_contract = env.contract.address.into_string();
 
-    let vote_tokens = deps
-        .querier
-        .query_balance(info.sender.clone(), VOTE_DENOM)?;
+    let inst_msg = ProposalInstantiateMsg {
+        proposer: info.sender.to_string(),
+        distribution_contract: config.distribution_contract.to_string(),
+        membership_contract: membership_contract.clone(),
+        kind: ProposalKind::Removal {
+            member: owner.to_string(),
+        },
+        expiration,
+        quorum: config.quorum,
+        threshold: config.threshold,
+        vote_token: config.vote_token.clone(),
+        escrow: vote_tokens,
+    };
+    let inst_msg = WasmMsg::Instantiate {
+        admin: Some(membership_contract),
+        code_id: config.proposal_code_id,
+        msg: to_json_binary(&inst_msg)?,
+        funds: vec![],
+        label: format!("{} Removal Proposal", owner),
+    };
+    let inst_msg = SubMsg::reply_on_success(inst_msg, REMOVAL_PROPOSAL_INSTANTIATION_REPLY_ID);
+
+    let lock_msg = proposer_lock_msg(
+        deps.as_ref(),
+        &config,
+        &info.sender,
+        vote_tokens,
+        env.block.height,
+    )?;
+
+    let resp = Response::new()
+        .add_submessage(inst_msg)
+        .add_message(lock_msg)
+        .add_attribute("action", "propose_removal")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("owner", owner.as_str());
+    Ok(resp)
+}
+
+pub fn vote_member_proposal(
+    mut deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    voter_proxy: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let voter_proxy = deps.api.addr_validate(&voter_proxy)?;
+
+    // sender must be the proposal contract of an existing candidate
+    ensure!(
+        candidates()
+            .idx
+            .proposal
+            .item(deps.storage, info.sender.clone())?
+            .is_some(),
+        ContractError::NotProposedMember
+    );
+
+    // vote tokens are held and voted by the member proxy
+    let voter = members()
+        .may_load(deps.storage, &voter_proxy)?
+        .ok_or(ContractError::NotAMember)?
+        .owner;
 
     let config = CONFIG.load(deps.storage)?;
+    super::escrow_delegations(deps.branch(), &config, &info.sender, &voter_proxy)?;
+    let lock_msg = DistributionExecMsg::LockVoteTokens {
+        voter: voter.to_string(),
+        amount,
+    };
+    let lock_msg = super::distribution_msg(&config, &lock_msg, vec![])?;
 
-    let total_vote_tokens_in_circulation = distribution::state::TOTAL_VOTE_TOKENS_IN_CIRCULATION
-        .query(&deps.querier, config.distribution_contract)?;
+    let mut resp = Response::new().add_message(lock_msg);
 
-    let mut resp = Response::new()
+    // a voter sticks to one option, yes votes are shares of the joining fee
+    let yes_votes = proposal::state::VOTER_TOKENS.query(
+        &deps.querier,
+        info.sender.clone(),
+        (VoteOption::Yes.as_str(), &voter_proxy),
+    )?;
+    if yes_votes.is_some() {
+        let shares_msg = DistributionExecMsg::AddRewardShares {
+            proposal: info.sender.to_string(),
+            voter_proxy: voter_proxy.to_string(),
+            amount,
+            height: proposal::state::START_HEIGHT.query(&deps.querier, info.sender.clone())?,
+        };
+        resp = resp.add_message(super::distribution_msg(&config, &shares_msg, vec![])?);
+    }
+
+    let mut resp = resp
         .add_attribute("action", "vote_member_proposal")
         .add_attribute("sender", info.sender.as_str());
 
-    if vote_tokens.amount < total_vote_tokens_in_circulation.amount / Uint128::new(2) {
+    if !is_passing(deps.as_ref(), &info.sender)? {
         resp = resp.add_attribute("passed", "no");
         return Ok(resp);
     }
@@ -128,7 +309,7 @@ pub fn vote_member_proposal(
     let msg = ProposalExecMsg::Pass {};
     let msg = WasmMsg::Execute {
         contract_addr: info.sender.into_string(),
-        msg: to_binary(&msg)?,
+        msg: to_json_binary(&msg)?,
         funds: vec![],
     };
 
@@ -137,44 +318,428 @@ pub fn vote_member_proposal(
     Ok(resp.add_submessage(msg))
 }
 
-pub fn new_member(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn vote_generic_proposal(
+    mut deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    voter_proxy: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let voter_proxy = deps.api.addr_validate(&voter_proxy)?;
+
+    // sender must be an open generic proposal contract
+    ensure!(
+        GENERIC_PROPOSALS.has(deps.storage, &info.sender),
+        ContractError::NotGenericProposal
+    );
+
+    // vote tokens are held and voted by the member proxy
+    let voter = members()
+        .may_load(deps.storage, &voter_proxy)?
+        .ok_or(ContractError::NotAMember)?
+        .owner;
+
+    let config = CONFIG.load(deps.storage)?;
+    super::escrow_delegations(deps.branch(), &config, &info.sender, &voter_proxy)?;
+    let lock_msg = DistributionExecMsg::LockVoteTokens {
+        voter: voter.to_string(),
+        amount,
+    };
+    let lock_msg = super::distribution_msg(&config, &lock_msg, vec![])?;
+
+    let mut resp = Response::new()
+        .add_message(lock_msg)
+        .add_attribute("action", "vote_generic_proposal")
+        .add_attribute("sender", info.sender.as_str());
+
+    if !is_passing(deps.as_ref(), &info.sender)? {
+        resp = resp.add_attribute("passed", "no");
+        return Ok(resp);
+    }
+    resp = resp.add_attribute("passed", "yes");
+
+    GENERIC_PROPOSALS.remove(deps.storage, &info.sender);
+
+    let proposal: GenericProposalResp = deps
+        .querier
+        .query_wasm_smart(info.sender.clone(), &ProposalQueryMsg::GenericProposal {})?;
+
+    let msg = ProposalExecMsg::Pass {};
+    let msg = WasmMsg::Execute {
+        contract_addr: info.sender.to_string(),
+        msg: to_json_binary(&msg)?,
+        funds: vec![],
+    };
+    let release_msgs = release_escrow(deps.branch(), &config, &info.sender)?;
+
+    // proposal messages are executed on behalf of membership, one failing is reverted on its own
+    // so the proposal still passes and its escrow is released
+    if !proposal.msgs.is_empty() {
+        let executing = ExecutingProposal {
+            proposal: info.sender.clone(),
+            pending: proposal.msgs.len() as u32,
+        };
+        EXECUTING_PROPOSAL.save(deps.storage, &executing)?;
+    }
+    let proposal_msgs = proposal
+        .msgs
+        .into_iter()
+        .map(|msg| SubMsg::reply_always(msg, GENERIC_PROPOSAL_MSG_REPLY_ID));
+
+    Ok(resp
+        .add_message(msg)
+        .add_messages(release_msgs)
+        .add_submessages(proposal_msgs))
+}
+
+pub fn vote_removal_proposal(
+    mut deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    voter_proxy: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let voter_proxy = deps.api.addr_validate(&voter_proxy)?;
+
+    // sender must be an open removal proposal contract
+    let (member, _) = removals()
+        .idx
+        .proposal
+        .item(deps.storage, info.sender.clone())?
+        .ok_or(ContractError::NotRemovalProposal)?;
+    let member = Addr::unchecked(String::from_utf8(member).map_err(StdError::from)?);
+
+    // vote tokens are held and voted by the member proxy
+    let voter = members()
+        .may_load(deps.storage, &voter_proxy)?
+        .ok_or(ContractError::NotAMember)?
+        .owner;
+
+    let config = CONFIG.load(deps.storage)?;
+    super::escrow_delegations(deps.branch(), &config, &info.sender, &voter_proxy)?;
+    let lock_msg = DistributionExecMsg::LockVoteTokens {
+        voter: voter.to_string(),
+        amount,
+    };
+    let lock_msg = super::distribution_msg(&config, &lock_msg, vec![])?;
+
+    let mut resp = Response::new()
+        .add_message(lock_msg)
+        .add_attribute("action", "vote_removal_proposal")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("owner", member.as_str());
+
+    if !is_passing(deps.as_ref(), &info.sender)? {
+        resp = resp.add_attribute("passed", "no");
+        return Ok(resp);
+    }
+    resp = resp.add_attribute("passed", "yes");
+
+    removals().remove(deps.storage, &member)?;
+
+    let msg = ProposalExecMsg::Pass {};
+    let msg = WasmMsg::Execute {
+        contract_addr: info.sender.to_string(),
+        msg: to_json_binary(&msg)?,
+        funds: vec![],
+    };
+    let release_msgs = release_escrow(deps.branch(), &config, &info.sender)?;
+    resp = resp.add_message(msg).add_messages(release_msgs);
+
+    // the member may have left on its own while the proposal was open
+    if let Some((proxy, _)) = members().idx.owner.item(deps.storage, member)? {
+        let proxy = String::from_utf8(proxy).map_err(StdError::from)?;
+        let msg = WasmMsg::Execute {
+            contract_addr: proxy,
+            msg: to_json_binary(&ProxyExecMsg::Leave {})?,
+            funds: vec![],
+        };
+        resp = resp.add_message(msg);
+    }
+
+    Ok(resp)
+}
+
+/// Tally of the proposal against quorum and threshold it was created with, out of the vote
+/// tokens in circulation at its start height
+fn is_passing(deps: Deps, proposal: &Addr) -> Result<bool, ContractError> {
+    let tally = proposal::state::TALLY.query(&deps.querier, proposal.clone())?;
+    let rules = proposal::state::CONFIG.query(&deps.querier, proposal.clone())?;
+    let start_height = proposal::state::START_HEIGHT.query(&deps.querier, proposal.clone())?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let total: VotingPowerResp = deps.querier.query_wasm_smart(
+        config.distribution_contract,
+        &DistributionQueryMsg::TotalVotingPower {
+            height: Some(start_height),
+        },
+    )?;
+
+    Ok(tally.is_passed(total.power, rules.quorum, rules.threshold))
+}
+
+/// Proposer escrow is a yes vote, bound by the voting power at the beginning of the block the
+/// proposal starts in
+fn proposer_lock_msg(
+    deps: Deps,
+    config: &Config,
+    proposer: &Addr,
+    amount: Uint128,
+    height: u64,
+) -> Result<WasmMsg, ContractError> {
+    let owner = members()
+        .may_load(deps.storage, proposer)?
+        .ok_or(ContractError::Unauthorized)?
+        .owner;
+
+    let power: VotingPowerResp = deps.querier.query_wasm_smart(
+        &config.distribution_contract,
+        &DistributionQueryMsg::VotingPower {
+            address: owner.to_string(),
+            height: Some(height),
+        },
+    )?;
+    ensure!(
+        amount <= power.power,
+        ContractError::InsufficientVotingPower { power: power.power }
+    );
+
+    let lock_msg = DistributionExecMsg::LockVoteTokens {
+        voter: owner.into_string(),
+        amount,
+    };
+    Ok(super::distribution_msg(config, &lock_msg, vec![])?)
+}
+
+/// Unlocks and burns the escrow a passed proposal sends back to the distribution reserve
+fn release_escrow(
+    deps: DepsMut,
+    config: &Config,
+    proposal: &Addr,
+) -> Result<Vec<WasmMsg>, ContractError> {
+    let amount = proposal::state::TALLY
+        .query(&deps.querier, proposal.clone())?
+        .total();
+
+    let unlock_msg = DistributionExecMsg::UnlockVoteTokens {
+        amount,
+        refunds: vec![],
+    };
+    let burn_msg = DistributionExecMsg::BurnVoteTokens {
+        amount,
+        holder: None,
+    };
+
+    let mut msgs = vec![
+        super::distribution_msg(config, &unlock_msg, vec![])?,
+        super::distribution_msg(config, &burn_msg, vec![])?,
+    ];
+    msgs.extend(spend_delegations(deps, config, proposal)?);
+
+    Ok(msgs)
+}
+
+/// Delegated vote tokens escrowed in a proposal whose escrow is burned are spent, they are taken
+/// off the delegations of each voter pro-rata
+fn spend_delegations(
+    deps: DepsMut,
+    config: &Config,
+    proposal: &Addr,
+) -> Result<Vec<WasmMsg>, ContractError> {
+    let escrows = DELEGATED_ESCROW
+        .prefix(proposal)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut msgs = vec![];
+    for (voter_proxy, escrow) in escrows {
+        DELEGATED_ESCROW.remove(deps.storage, (proposal, &voter_proxy));
+        release_escrowed_delegations(deps.storage, &voter_proxy, escrow)?;
+
+        let delegations = super::received_delegations(deps.as_ref(), config, &voter_proxy)?;
+        let spent = split_delegations(&delegations, escrow);
+        for (delegation, amount) in delegations.into_iter().zip(spent) {
+            if amount.is_zero() {
+                continue;
+            }
+            let release_msg = DistributionExecMsg::ReleaseDelegation {
+                delegator_proxy: delegation.proxy,
+                delegate_proxy: voter_proxy.to_string(),
+                amount,
+            };
+            msgs.push(super::distribution_msg(config, &release_msg, vec![])?);
+        }
+    }
+
+    Ok(msgs)
+}
+
+/// Takes the vote tokens the proposal refunded to the voter proxy off its delegated escrow there,
+/// delegated ones are refunded first. Returns the delegated part of the refund.
+fn refund_delegations(
+    storage: &mut dyn Storage,
+    proposal: &Addr,
+    voter_proxy: &Addr,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    let escrow = DELEGATED_ESCROW
+        .may_load(storage, (proposal, voter_proxy))?
+        .unwrap_or_default();
+    let refunded = escrow.min(amount);
+    if refunded.is_zero() {
+        return Ok(refunded);
+    }
+
+    if refunded == escrow {
+        DELEGATED_ESCROW.remove(storage, (proposal, voter_proxy));
+    } else {
+        DELEGATED_ESCROW.save(storage, (proposal, voter_proxy), &(escrow - refunded))?;
+    }
+    release_escrowed_delegations(storage, voter_proxy, refunded)?;
+
+    Ok(refunded)
+}
+
+fn release_escrowed_delegations(
+    storage: &mut dyn Storage,
+    voter_proxy: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let escrowed = ESCROWED_DELEGATIONS
+        .may_load(storage, voter_proxy)?
+        .unwrap_or_default()
+        .saturating_sub(amount);
+    if escrowed.is_zero() {
+        ESCROWED_DELEGATIONS.remove(storage, voter_proxy);
+        Ok(())
+    } else {
+        ESCROWED_DELEGATIONS.save(storage, voter_proxy, &escrowed)
+    }
+}
+
+/// Delegated vote tokens the delegate proxy still holds, split pro-rata among its delegations
+fn held_delegations(
+    storage: &dyn Storage,
+    delegations: &[DelegationResp],
+    delegate_proxy: &Addr,
+) -> StdResult<Vec<Uint128>> {
+    let delegated: Uint128 = delegations.iter().map(|delegation| delegation.amount).sum();
+    let escrowed = ESCROWED_DELEGATIONS
+        .may_load(storage, delegate_proxy)?
+        .unwrap_or_default();
+
+    Ok(split_delegations(
+        delegations,
+        delegated.saturating_sub(escrowed),
+    ))
+}
+
+/// Splits `amount` pro-rata to the delegations, rounding down and handing the remainder out in
+/// order. Parts do not exceed their delegations.
+fn split_delegations(delegations: &[DelegationResp], amount: Uint128) -> Vec<Uint128> {
+    let total: Uint128 = delegations.iter().map(|delegation| delegation.amount).sum();
+    if total.is_zero() {
+        return vec![Uint128::zero(); delegations.len()];
+    }
+
+    let amount = amount.min(total);
+    let mut parts: Vec<_> = delegations
+        .iter()
+        .map(|delegation| amount.multiply_ratio(delegation.amount, total))
+        .collect();
+
+    let mut remainder = amount - parts.iter().sum::<Uint128>();
+    for (part, delegation) in parts.iter_mut().zip(delegations) {
+        let room = (delegation.amount - *part).min(remainder);
+        *part += room;
+        remainder -= room;
+    }
+
+    parts
+}
+
+pub fn new_member(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
     ensure!(
         !members().has(deps.storage, &info.sender),
         ContractError::AlreadyAMember
     );
 
-    let new_member_vote_amount = must_pay(&info, VOTE_DENOM)?;
+    ensure!(
+        PROPOSALS.has(deps.storage, &info.sender),
+        ContractError::Unauthorized
+    );
+
+    let config = CONFIG.load(deps.storage)?;
+    let escrowed_vote_amount = must_pay(&info, &config.vote_token.denom())?;
 
     let proposal_addr = info.sender;
     let proposal_owner = proposal::state::OWNER.query(&deps.querier, proposal_addr.clone())?;
 
+    // only the proposal the candidate is pending on admits it
+    let candidate_proposal = candidates().load(deps.storage, &proposal_owner)?;
     ensure!(
-        candidates().has(deps.storage, &proposal_owner),
+        candidate_proposal == proposal_addr,
         ContractError::NotProposedMember
     );
 
     candidates().remove(deps.storage, &proposal_owner)?;
 
-    let config = CONFIG.load(deps.storage)?;
+    let initial_votes = config.vote_token.coin(config.initial_votes);
+
+    PENDING_MEMBER.save(
+        deps.storage,
+        &PendingMember {
+            proposal: Some(proposal_addr.clone()),
+            initial_votes: initial_votes.clone(),
+        },
+    )?;
 
     let membership_contract = env.contract.address.into_string();
+
+    // voters' escrow leaves the proposal for the reserve, the new member proxy gets initial
+    // votes minted once instantiated
+    let unlock_msg = DistributionExecMsg::UnlockVoteTokens {
+        amount: escrowed_vote_amount,
+        refunds: vec![],
+    };
+    let burn_msg = DistributionExecMsg::BurnVoteTokens {
+        amount: escrowed_vote_amount,
+        holder: None,
+    };
+    let mut supply_msgs = vec![
+        super::distribution_msg(&config, &unlock_msg, vec![])?,
+        config.vote_token.send(
+            &config.distribution_contract,
+            escrowed_vote_amount,
+            &burn_msg,
+        )?,
+    ];
+    supply_msgs.extend(spend_delegations(deps.branch(), &config, &proposal_addr)?);
+
     let msg = ProxyInstantiateMsg {
         owner: proposal_owner.clone().into_string(),
         distribution_contract: config.distribution_contract.to_string(),
         membership_contract: membership_contract.clone(),
+        vote_token: config.vote_token.clone(),
     };
 
     let msg = WasmMsg::Instantiate {
         admin: Some(membership_contract),
         code_id: config.proxy_code_id,
-        msg: to_binary(&msg)?,
-        funds: coins(new_member_vote_amount.u128(), VOTE_DENOM),
+        msg: to_json_binary(&msg)?,
+        funds: vec![],
         label: format!("{} Proxy", proposal_owner),
     };
 
     let msg = SubMsg::reply_on_success(msg, super::PROXY_INSTANTIATION_REPLY_ID);
 
     let resp = Response::new()
+        .add_messages(supply_msgs)
         .add_submessage(msg)
         .add_attribute("action", "new_member")
         .add_attribute("sender", proposal_addr.as_str())
@@ -182,3 +747,610 @@ pub fn new_member(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response
 
     Ok(resp)
 }
+
+pub fn fund_pool(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let admin = deps
+        .querier
+        .query_wasm_contract_info(env.contract.address)?
+        .admin;
+    ensure!(
+        admin == Some(info.sender.to_string()),
+        ContractError::Unauthorized
+    );
+
+    ensure!(
+        AWAITING_POOL.exists(deps.storage),
+        ContractError::PoolAlreadyFunded
+    );
+    AWAITING_POOL.remove(deps.storage);
+
+    let config = CONFIG.load(deps.storage)?;
+    let pool = must_pay(&info, &config.vote_token.denom())?;
+
+    let initial_members: Vec<_> = members()
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, member)| member.proposal.is_none())
+        .collect();
+
+    let vote_tokens_per_member = super::pool_share(&config, pool, initial_members.len());
+    ensure!(
+        vote_tokens_per_member >= Uint128::new(1),
+        ContractError::InitialisationLessVoteTokens
+    );
+
+    // whole pool goes to the distribution reserve, initial members get theirs minted from it
+    let mut resp = Response::new()
+        .add_message(
+            config
+                .vote_token
+                .transfer::<Empty>(&config.distribution_contract, pool)?,
+        )
+        .add_attribute("action", "fund_pool")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("pool", pool.to_string());
+
+    for (proxy, mut member) in initial_members {
+        let mint_msg = DistributionExecMsg::MintVoteTokens {
+            recipient: proxy.to_string(),
+            amount: vote_tokens_per_member,
+            holder: Some(member.owner.to_string()),
+        };
+        resp = resp.add_message(super::distribution_msg(&config, &mint_msg, vec![])?);
+
+        member.initial_votes = config.vote_token.coin(vote_tokens_per_member);
+        members().save(deps.storage, &proxy, &member)?;
+    }
+
+    Ok(resp)
+}
+
+pub fn member_left(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let vote_tokens = may_pay(&info, &config.vote_token.denom())?;
+    let proxy = info.sender;
+
+    let member = members()
+        .may_load(deps.storage, &proxy)?
+        .ok_or(ContractError::Unauthorized)?;
+
+    members().remove(deps.storage, &proxy)?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "member_left")
+        .add_attribute("sender", proxy.as_str())
+        .add_attribute("owner", member.owner.as_str());
+
+    let delegations: DelegationsResp = deps.querier.query_wasm_smart(
+        &config.distribution_contract,
+        &DistributionQueryMsg::Delegations {
+            proxy: proxy.to_string(),
+        },
+    )?;
+    let leaving = ProxyMemberData {
+        owner_addr: member.owner.to_string(),
+        proxy_addr: proxy.to_string(),
+    };
+
+    // delegated vote tokens the proxy still held go back to their delegators, those escrowed in
+    // proposals stay delegated and go back when refunded
+    let mut kept = vote_tokens;
+    let held = held_delegations(deps.storage, &delegations.received, &proxy)?;
+    for (delegation, returned) in delegations.received.into_iter().zip(held) {
+        let returned = returned.min(kept);
+        if returned.is_zero() {
+            continue;
+        }
+        kept -= returned;
+
+        let delegator_proxy = Addr::unchecked(delegation.proxy);
+        resp = resp.add_message(config.vote_token.transfer(&delegator_proxy, returned)?);
+
+        let delegator = members().load(deps.storage, &delegator_proxy)?;
+        let undelegate_msg = DistributionExecMsg::UndelegateVoteTokens {
+            delegator: ProxyMemberData {
+                owner_addr: delegator.owner.into_string(),
+                proxy_addr: delegator_proxy.into_string(),
+            },
+            delegate: leaving.clone(),
+            amount: returned,
+        };
+        resp = resp.add_message(super::distribution_msg(&config, &undelegate_msg, vec![])?);
+    }
+
+    // vote tokens the member delegated are taken back from its delegates as far as they still
+    // hold them and burned with its own, those they escrowed in proposals are left to them
+    let mut reclaimed = Uint128::zero();
+    for delegation in delegations.delegated {
+        let delegate_proxy = Addr::unchecked(delegation.proxy);
+        let mut returned = Uint128::zero();
+        // delegates who left hold none of them anymore
+        if members().has(deps.storage, &delegate_proxy) {
+            let received = super::received_delegations(deps.as_ref(), &config, &delegate_proxy)?;
+            let held = held_delegations(deps.storage, &received, &delegate_proxy)?;
+            returned = received
+                .iter()
+                .zip(held)
+                .find(|(received, _)| received.proxy == proxy.as_str())
+                .map(|(_, held)| held)
+                .unwrap_or_default();
+        }
+
+        if !returned.is_zero() {
+            reclaimed += returned;
+            let return_msg = ProxyExecMsg::ReturnDelegation {
+                recipient: config.distribution_contract.to_string(),
+                amount: returned,
+            };
+            resp = resp.add_message(WasmMsg::Execute {
+                contract_addr: delegate_proxy.to_string(),
+                msg: to_json_binary(&return_msg)?,
+                funds: vec![],
+            });
+
+            let delegate = members().load(deps.storage, &delegate_proxy)?;
+            let undelegate_msg = DistributionExecMsg::UndelegateVoteTokens {
+                delegator: leaving.clone(),
+                delegate: ProxyMemberData {
+                    owner_addr: delegate.owner.into_string(),
+                    proxy_addr: delegate_proxy.to_string(),
+                },
+                amount: returned,
+            };
+            resp = resp.add_message(super::distribution_msg(&config, &undelegate_msg, vec![])?);
+        }
+
+        let released = delegation.amount - returned;
+        if !released.is_zero() {
+            release_escrowed_delegations(deps.storage, &delegate_proxy, released)?;
+            let release_msg = DistributionExecMsg::ReleaseDelegation {
+                delegator_proxy: proxy.to_string(),
+                delegate_proxy: delegate_proxy.into_string(),
+                amount: released,
+            };
+            resp = resp.add_message(super::distribution_msg(&config, &release_msg, vec![])?);
+        }
+    }
+
+    // its yes votes in proposals not joined yet stop being shares of their joining fee
+    let drop_msg = DistributionExecMsg::DropMemberShares {
+        voter_proxy: proxy.to_string(),
+    };
+    resp = resp.add_message(super::distribution_msg(&config, &drop_msg, vec![])?);
+
+    // vote tokens the proxy kept go back to the distribution pool, voting power goes with the
+    // member even without anything to burn
+    let msg = config.vote_token.send(
+        &config.distribution_contract,
+        kept,
+        &DistributionExecMsg::BurnVoteTokens {
+            amount: kept + reclaimed,
+            holder: Some(member.owner.into_string()),
+        },
+    )?;
+    resp = resp
+        .add_message(msg)
+        .add_attribute("vote_tokens", (kept + reclaimed).to_string());
+
+    Ok(resp)
+}
+
+pub fn delegate(deps: DepsMut, info: MessageInfo, to: String) -> Result<Response, ContractError> {
+    let to = deps.api.addr_validate(&to)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let vote_tokens = must_pay(&info, &config.vote_token.denom())?;
+
+    let delegator = members()
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::Unauthorized)?;
+    let delegate_proxy = owner_proxy(deps.as_ref(), &to)?;
+    ensure!(delegate_proxy != info.sender, ContractError::SelfDelegation);
+
+    // the delegate proxy holds and votes the delegated vote tokens
+    let transfer_msg = config.vote_token.transfer(&delegate_proxy, vote_tokens)?;
+    let delegate_msg = DistributionExecMsg::DelegateVoteTokens {
+        delegator: ProxyMemberData {
+            owner_addr: delegator.owner.into_string(),
+            proxy_addr: info.sender.to_string(),
+        },
+        delegate: ProxyMemberData {
+            owner_addr: to.to_string(),
+            proxy_addr: delegate_proxy.to_string(),
+        },
+        amount: vote_tokens,
+    };
+
+    let resp = Response::new()
+        .add_message(transfer_msg)
+        .add_message(super::distribution_msg(&config, &delegate_msg, vec![])?)
+        .add_attribute("action", "delegate")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("delegate", to.as_str())
+        .add_attribute("amount", vote_tokens.to_string());
+
+    Ok(resp)
+}
+
+pub fn undelegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    from: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let from = deps.api.addr_validate(&from)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let delegator = members()
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::Unauthorized)?;
+    let delegate_proxy = owner_proxy(deps.as_ref(), &from)?;
+
+    let received = super::received_delegations(deps.as_ref(), &config, &delegate_proxy)?;
+    let held = held_delegations(deps.storage, &received, &delegate_proxy)?;
+    let (delegated, held) = received
+        .into_iter()
+        .zip(held)
+        .find(|(delegation, _)| delegation.proxy == info.sender.as_str())
+        .map(|(delegation, held)| (delegation.amount, held))
+        .unwrap_or_default();
+    ensure!(
+        amount <= delegated,
+        ContractError::InsufficientDelegation { delegated }
+    );
+
+    // vote tokens the delegate escrowed in proposals stay delegated until they are refunded to
+    // its proxy, only the rest goes back
+    let returned = amount.min(held);
+    let mut resp = Response::new()
+        .add_attribute("action", "undelegate")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("delegate", from.as_str())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("returned", returned.to_string());
+    if returned.is_zero() {
+        return Ok(resp);
+    }
+
+    let undelegate_msg = DistributionExecMsg::UndelegateVoteTokens {
+        delegator: ProxyMemberData {
+            owner_addr: delegator.owner.into_string(),
+            proxy_addr: info.sender.to_string(),
+        },
+        delegate: ProxyMemberData {
+            owner_addr: from.to_string(),
+            proxy_addr: delegate_proxy.to_string(),
+        },
+        amount: returned,
+    };
+    let return_msg = ProxyExecMsg::ReturnDelegation {
+        recipient: info.sender.into_string(),
+        amount: returned,
+    };
+    resp = resp
+        .add_message(super::distribution_msg(&config, &undelegate_msg, vec![])?)
+        .add_message(WasmMsg::Execute {
+            contract_addr: delegate_proxy.into_string(),
+            msg: to_json_binary(&return_msg)?,
+            funds: vec![],
+        });
+
+    Ok(resp)
+}
+
+/// Proxy of the member `owner`
+fn owner_proxy(deps: Deps, owner: &Addr) -> Result<Addr, ContractError> {
+    let (proxy, _) = members()
+        .idx
+        .owner
+        .item(deps.storage, owner.clone())?
+        .ok_or(ContractError::NotAMember)?;
+    let proxy = String::from_utf8(proxy).map_err(StdError::from)?;
+
+    Ok(Addr::unchecked(proxy))
+}
+
+pub fn vote_retracted(
+    deps: DepsMut,
+    info: MessageInfo,
+    voter_proxy: String,
+    amount: Uint128,
+    yes: bool,
+) -> Result<Response, ContractError> {
+    let proposal_addr = info.sender;
+    let voter_proxy = deps.api.addr_validate(&voter_proxy)?;
+
+    ensure!(
+        PROPOSALS.has(deps.storage, &proposal_addr),
+        ContractError::Unauthorized
+    );
+
+    let config = CONFIG.load(deps.storage)?;
+    refund_delegations(deps.storage, &proposal_addr, &voter_proxy, amount)?;
+
+    // retracted vote tokens were refunded to the voter proxy, they count for the voter again
+    let refunds = members()
+        .may_load(deps.storage, &voter_proxy)?
+        .map(|member| VoterTokens {
+            voter: member.owner.into_string(),
+            amount,
+        })
+        .into_iter()
+        .collect();
+    let unlock_msg = DistributionExecMsg::UnlockVoteTokens { amount, refunds };
+
+    let mut resp = Response::new()
+        .add_message(super::distribution_msg(&config, &unlock_msg, vec![])?)
+        .add_attribute("action", "vote_retracted")
+        .add_attribute("sender", proposal_addr.as_str())
+        .add_attribute("voter_proxy", voter_proxy.as_str());
+
+    // only yes votes on member proposals are shares of the joining fee
+    let is_member_proposal = candidates()
+        .idx
+        .proposal
+        .item(deps.storage, proposal_addr.clone())?
+        .is_some();
+    if yes && is_member_proposal {
+        let shares_msg = DistributionExecMsg::RemoveRewardShares {
+            proposal: proposal_addr.into_string(),
+            voter_proxy: voter_proxy.into_string(),
+            amount,
+        };
+        resp = resp.add_message(super::distribution_msg(&config, &shares_msg, vec![])?);
+    }
+
+    Ok(resp)
+}
+
+pub fn proposal_closed(
+    deps: DepsMut,
+    info: MessageInfo,
+    refunds: Vec<VoterTokens>,
+) -> Result<Response, ContractError> {
+    let proposal_addr = info.sender;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "proposal_closed")
+        .add_attribute("sender", proposal_addr.as_str());
+
+    if GENERIC_PROPOSALS.has(deps.storage, &proposal_addr) {
+        GENERIC_PROPOSALS.remove(deps.storage, &proposal_addr);
+    } else if let Some((member, _)) = removals()
+        .idx
+        .proposal
+        .item(deps.storage, proposal_addr.clone())?
+    {
+        let member = Addr::unchecked(String::from_utf8(member).map_err(StdError::from)?);
+        removals().remove(deps.storage, &member)?;
+        resp = resp.add_attribute("owner", member.as_str());
+    } else {
+        ensure!(
+            candidates()
+                .idx
+                .proposal
+                .item(deps.storage, proposal_addr.clone())?
+                .is_some(),
+            ContractError::NotProposedMember
+        );
+
+        let proposal_owner = proposal::state::OWNER.query(&deps.querier, proposal_addr.clone())?;
+        candidates().remove(deps.storage, &proposal_owner)?;
+        resp = resp.add_attribute("owner", proposal_owner.as_str());
+
+        // yes votes were shares of a joining fee which is never paid
+        let config = CONFIG.load(deps.storage)?;
+        let drop_msg = DistributionExecMsg::DropRewardShares {
+            proposal: proposal_addr.to_string(),
+        };
+        resp = resp.add_message(super::distribution_msg(&config, &drop_msg, vec![])?);
+    }
+
+    // escrow was refunded to the proxies of members who are still around, it stays in
+    // circulation and they get their voting power back. That of members who left went to
+    // distribution, it is burned, their yes votes stopped being shares of the joining fee as they
+    // left. Vote tokens delegated to them among it are handed back to their delegators instead.
+    let config = CONFIG.load(deps.storage)?;
+    let amount = proposal::state::TALLY
+        .query(&deps.querier, proposal_addr.clone())?
+        .total();
+    let mut member_refunds = vec![];
+    let mut burned = Uint128::zero();
+    let mut delegation_msgs = vec![];
+    for refund in refunds {
+        let proxy = deps.api.addr_validate(&refund.voter)?;
+        let delegated = refund_delegations(deps.storage, &proposal_addr, &proxy, refund.amount)?;
+        if let Some(member) = members().may_load(deps.storage, &proxy)? {
+            member_refunds.push(VoterTokens {
+                voter: member.owner.into_string(),
+                amount: refund.amount,
+            });
+            continue;
+        }
+
+        burned += refund.amount;
+        let delegations = super::received_delegations(deps.as_ref(), &config, &proxy)?;
+        let handed_back = split_delegations(&delegations, delegated);
+        for (delegation, amount) in delegations.into_iter().zip(handed_back) {
+            if amount.is_zero() {
+                continue;
+            }
+            let delegator_proxy = Addr::unchecked(delegation.proxy);
+            let delegator = members().load(deps.storage, &delegator_proxy)?;
+            let release_msg = DistributionExecMsg::ReleaseDelegation {
+                delegator_proxy: delegator_proxy.to_string(),
+                delegate_proxy: proxy.to_string(),
+                amount,
+            };
+            let mint_msg = DistributionExecMsg::MintVoteTokens {
+                recipient: delegator_proxy.into_string(),
+                amount,
+                holder: Some(delegator.owner.into_string()),
+            };
+            delegation_msgs.push(super::distribution_msg(&config, &release_msg, vec![])?);
+            delegation_msgs.push(super::distribution_msg(&config, &mint_msg, vec![])?);
+        }
+    }
+
+    let unlock_msg = DistributionExecMsg::UnlockVoteTokens {
+        amount,
+        refunds: member_refunds,
+    };
+    resp = resp.add_message(super::distribution_msg(&config, &unlock_msg, vec![])?);
+
+    if !burned.is_zero() {
+        let burn_msg = DistributionExecMsg::BurnVoteTokens {
+            amount: burned,
+            holder: None,
+        };
+        resp = resp
+            .add_message(super::distribution_msg(&config, &burn_msg, vec![])?)
+            .add_attribute("burned", burned.to_string());
+    }
+
+    Ok(resp.add_messages(delegation_msgs))
+}
+
+pub fn migrate_children(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    code_id: u64,
+    kind: ChildKind,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let admin = deps
+        .querier
+        .query_wasm_contract_info(env.contract.address)?
+        .admin;
+    ensure!(
+        admin == Some(info.sender.to_string()),
+        ContractError::Unauthorized
+    );
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let (children, msg) = match kind {
+        ChildKind::Proxy => {
+            config.proxy_code_id = code_id;
+            let proxies = members()
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+            (proxies, to_json_binary(&ProxyMigrateMsg {})?)
+        }
+        ChildKind::Proposal => {
+            config.proposal_code_id = code_id;
+            let proposals = PROPOSALS
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+            (proposals, to_json_binary(&ProposalMigrateMsg {})?)
+        }
+        ChildKind::Distribution => (
+            vec![config.distribution_contract.clone()],
+            to_json_binary(&DistributionMigrateMsg {})?,
+        ),
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "migrate_children")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("code_id", code_id.to_string())
+        .add_attribute("migrated", children.len().to_string());
+
+    if let Some(last) = children.last() {
+        resp = resp.add_attribute("last", last.as_str());
+    }
+
+    let msgs = children.into_iter().map(|child| WasmMsg::Migrate {
+        contract_addr: child.into_string(),
+        new_code_id: code_id,
+        msg: msg.clone(),
+    });
+
+    Ok(resp.add_messages(msgs))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    update: ConfigUpdate,
+) -> Result<Response, ContractError> {
+    let admin = deps
+        .querier
+        .query_wasm_contract_info(env.contract.address.clone())?
+        .admin;
+    ensure!(
+        info.sender == env.contract.address || admin == Some(info.sender.to_string()),
+        ContractError::Unauthorized
+    );
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let mut event = Event::new("config_update");
+
+    if let Some(joining_fee) = update.joining_fee {
+        let fees: Vec<_> = joining_fee.iter().map(Coin::to_string).collect();
+        event = event.add_attribute("joining_fee", fees.join(","));
+        config.joining_fee = joining_fee;
+    }
+    if let Some(initial_votes) = update.initial_votes {
+        event = event.add_attribute("initial_votes", initial_votes.to_string());
+        config.initial_votes = initial_votes;
+    }
+    if let Some(part) = update.initial_vote_token_distribution_part {
+        event = event.add_attribute("initial_vote_token_distribution_part", part.to_string());
+        config.initial_vote_token_distribution_part = part;
+    }
+    if let Some(voting_period) = update.voting_period {
+        event = event.add_attribute("voting_period", voting_period.to_string());
+        config.voting_period = voting_period;
+    }
+    if let Some(quorum) = update.quorum {
+        event = event.add_attribute("quorum", quorum.to_string());
+        config.quorum = quorum;
+    }
+    if let Some(threshold) = update.threshold {
+        event = event.add_attribute("threshold", threshold.to_string());
+        config.threshold = threshold;
+    }
+    if let Some(code_id) = update.proxy_code_id {
+        event = event.add_attribute("proxy_code_id", code_id.to_string());
+        config.proxy_code_id = code_id;
+    }
+    if let Some(code_id) = update.proposal_code_id {
+        event = event.add_attribute("proposal_code_id", code_id.to_string());
+        config.proposal_code_id = code_id;
+    }
+
+    super::validate_config(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute("sender", info.sender.as_str());
+
+    // distribution owns the vote token price
+    if let Some(vote_token_price) = update.vote_token_price {
+        super::validate_vote_token_price(&config, &vote_token_price)?;
+
+        let msg = WasmMsg::Execute {
+            contract_addr: config.distribution_contract.into_string(),
+            msg: to_json_binary(&DistributionExecMsg::UpdateConfig { vote_token_price })?,
+            funds: vec![],
+        };
+        resp = resp.add_message(msg);
+    }
+
+    Ok(resp.add_event(event))
+}
@@ -1,12 +1,28 @@
 use crate::{
     error::ContractError,
-    state::{candidates, members},
+    state::{candidates, members, CONFIG, PROPOSALS},
+};
+use common::msg::{
+    membership::{
+        CandidateResp, DelegationResp, DelegationsResp, InfoResp, IsMemberResp, IsProposalResp,
+        IsProposedMemberResp, ListCandidatesResp, ListMembersResp, MemberCountResp,
+        MemberProfileResp, MemberResp, OwnerProxyResp, SupplyInvariantResp, TotalPowerAtHeightResp,
+        VotingPowerAtHeightResp,
+    },
+    WithdrawableResp,
+};
+use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult, Uint128};
+use cw2::get_contract_version;
+use cw_storage_plus::{Bound, Prefixer};
+use distribution::msg::{
+    DelegationResp as DistributionDelegationResp, DelegationsResp as DistributionDelegationsResp,
+    QueryMsg as DistributionQueryMsg, SupplyResp, VotingPowerResp,
 };
-use common::msg::membership::{IsMemberResp, IsProposedMemberResp, OwnerProxyResp};
-use cosmwasm_std::{Addr, Deps, Order, StdError, StdResult};
-use cw_storage_plus::Prefixer;
 use std::str;
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 pub fn is_member(deps: Deps, addr: String) -> StdResult<IsMemberResp> {
     let addr = deps.api.addr_validate(&addr)?;
     let ok = members().has(deps.storage, &addr);
@@ -21,18 +37,242 @@ pub fn is_proposed_member(deps: Deps, addr: String) -> StdResult<IsProposedMembe
     Ok(IsProposedMemberResp { ok })
 }
 
+pub fn is_proposal(deps: Deps, addr: String) -> StdResult<IsProposalResp> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let ok = PROPOSALS.has(deps.storage, &addr);
+
+    Ok(IsProposalResp { ok })
+}
+
 pub fn owner_proxy(deps: Deps, owner: String) -> StdResult<OwnerProxyResp> {
     let owner = deps.api.addr_validate(&owner)?;
-    let (pk, sk) = members()
+    let (pk, _) = members()
         .idx
         .owner
         .item(deps.storage, owner.clone())?
         .ok_or(StdError::generic_err("not an owner"))?;
 
-    let pk = str::from_utf8(&pk).unwrap();
+    let pk = str::from_utf8(&pk)?;
 
     Ok(OwnerProxyResp {
         owner: owner.into(),
         proxy: pk.to_string(),
     })
 }
+
+pub fn member_by_proxy(deps: Deps, proxy: String) -> StdResult<OwnerProxyResp> {
+    let proxy = deps.api.addr_validate(&proxy)?;
+    let member = members()
+        .may_load(deps.storage, &proxy)?
+        .ok_or(StdError::generic_err("not a member proxy"))?;
+
+    Ok(OwnerProxyResp {
+        owner: member.owner.into(),
+        proxy: proxy.into(),
+    })
+}
+
+pub fn member_profile(deps: Deps, owner: String) -> StdResult<MemberProfileResp> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let (pk, member) = members()
+        .idx
+        .owner
+        .item(deps.storage, owner.clone())?
+        .ok_or(StdError::generic_err("not an owner"))?;
+
+    let proxy = str::from_utf8(&pk)?.to_string();
+
+    let config = CONFIG.load(deps.storage)?;
+    let withdrawable: WithdrawableResp = deps.querier.query_wasm_smart(
+        config.distribution_contract,
+        &DistributionQueryMsg::Withdrawable {
+            proxy: proxy.clone(),
+        },
+    )?;
+
+    Ok(MemberProfileResp {
+        owner: owner.into(),
+        proxy,
+        joined_height: member.joined_height,
+        joined_time: member.joined_time,
+        proposal: member.proposal.map(Addr::into_string),
+        initial_votes: member.initial_votes,
+        withdrawable: withdrawable.funds,
+    })
+}
+
+pub fn list_members(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListMembersResp> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let start = start_after.as_ref().map(Bound::exclusive);
+    let config = CONFIG.load(deps.storage)?;
+
+    let members = members()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|member| -> StdResult<_> {
+            let (proxy, member) = member?;
+            let vote_tokens = config
+                .vote_token
+                .coin(config.vote_token.balance(&deps.querier, &proxy)?);
+
+            Ok(MemberResp {
+                owner: member.owner.into(),
+                proxy: proxy.into(),
+                vote_tokens,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(ListMembersResp { members })
+}
+
+pub fn list_candidates(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListCandidatesResp> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let candidates = candidates()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|candidate| -> StdResult<_> {
+            let (candidate, proposal) = candidate?;
+
+            Ok(CandidateResp {
+                candidate: candidate.into(),
+                proposal: proposal.into(),
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(ListCandidatesResp { candidates })
+}
+
+pub fn member_count(deps: Deps) -> StdResult<MemberCountResp> {
+    let count = members()
+        .keys_raw(deps.storage, None, None, Order::Ascending)
+        .count() as u64;
+
+    Ok(MemberCountResp { count })
+}
+
+pub fn supply_invariant(deps: Deps) -> StdResult<SupplyInvariantResp> {
+    let config = CONFIG.load(deps.storage)?;
+    let supply: SupplyResp = deps.querier.query_wasm_smart(
+        &config.distribution_contract,
+        &DistributionQueryMsg::Supply {},
+    )?;
+
+    let balance =
+        |addr: &Addr| -> StdResult<Uint128> { config.vote_token.balance(&deps.querier, addr) };
+
+    let mut held = Uint128::zero();
+    for proxy in members().keys(deps.storage, None, None, Order::Ascending) {
+        let proxy = proxy?;
+        held += balance(&proxy)?;
+    }
+
+    let mut locked = Uint128::zero();
+    for proposal in PROPOSALS.keys(deps.storage, None, None, Order::Ascending) {
+        locked += balance(&proposal?)?;
+    }
+
+    let actual_circulating = config.vote_token.coin(held + locked);
+    let actual_locked = config.vote_token.coin(locked);
+
+    Ok(SupplyInvariantResp {
+        holds: supply.circulating == actual_circulating && supply.locked == actual_locked,
+        recorded_circulating: supply.circulating,
+        actual_circulating,
+        recorded_locked: supply.locked,
+        actual_locked,
+    })
+}
+
+// voting power history is kept by distribution along with the vote token supply
+pub fn voting_power_at_height(
+    deps: Deps,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerAtHeightResp> {
+    let config = CONFIG.load(deps.storage)?;
+    let VotingPowerResp { power, height } = deps.querier.query_wasm_smart(
+        config.distribution_contract,
+        &DistributionQueryMsg::VotingPower { address, height },
+    )?;
+
+    Ok(VotingPowerAtHeightResp { power, height })
+}
+
+pub fn total_power_at_height(deps: Deps, height: Option<u64>) -> StdResult<TotalPowerAtHeightResp> {
+    let config = CONFIG.load(deps.storage)?;
+    let VotingPowerResp { power, height } = deps.querier.query_wasm_smart(
+        config.distribution_contract,
+        &DistributionQueryMsg::TotalVotingPower { height },
+    )?;
+
+    Ok(TotalPowerAtHeightResp { power, height })
+}
+
+pub fn info(deps: Deps) -> StdResult<InfoResp> {
+    Ok(InfoResp {
+        info: get_contract_version(deps.storage)?,
+    })
+}
+
+pub fn dao(env: Env) -> Addr {
+    env.contract.address
+}
+
+// delegations are recorded by distribution between member proxies
+pub fn delegations(deps: Deps, owner: String) -> StdResult<DelegationsResp> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let (pk, _) = members()
+        .idx
+        .owner
+        .item(deps.storage, owner)?
+        .ok_or(StdError::generic_err("not an owner"))?;
+
+    let proxy = str::from_utf8(&pk)?.to_string();
+
+    let config = CONFIG.load(deps.storage)?;
+    let delegations: DistributionDelegationsResp = deps.querier.query_wasm_smart(
+        config.distribution_contract,
+        &DistributionQueryMsg::Delegations { proxy },
+    )?;
+
+    // delegations escrowed by a delegate who left stay until the escrow is refunded
+    let member_delegation = |delegation: DistributionDelegationResp| -> StdResult<_> {
+        let proxy = Addr::unchecked(delegation.proxy);
+        let owner = match members().may_load(deps.storage, &proxy)? {
+            Some(member) => member.owner,
+            None => proxy::state::OWNER.query(&deps.querier, proxy.clone())?,
+        };
+        Ok(DelegationResp {
+            owner: owner.into(),
+            proxy: proxy.into(),
+            amount: delegation.amount,
+        })
+    };
+
+    Ok(DelegationsResp {
+        delegated: delegations
+            .delegated
+            .into_iter()
+            .map(member_delegation)
+            .collect::<StdResult<_>>()?,
+        received: delegations
+            .received
+            .into_iter()
+            .map(member_delegation)
+            .collect::<StdResult<_>>()?,
+    })
+}
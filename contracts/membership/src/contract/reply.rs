@@ -1,20 +1,22 @@
 use std::collections::HashMap;
 
-use common::{
-    keys::VOTE_DENOM,
-    msg::{ProposalMemberData, ProxyMemberData},
-};
+use common::msg::{ProposalGenericData, ProposalMemberData, ProxyMemberData};
 use cosmwasm_std::{
-    coin, from_binary, to_binary, Addr, BankMsg, Coin, Decimal, DepsMut, Env, Order, Response,
-    StdError, StdResult, SubMsg, SubMsgResponse, Uint128, WasmMsg,
+    ensure, from_json, to_json_binary, Addr, CosmosMsg, Deps, DepsMut, Empty, Env, Event, Order,
+    Response, StdError, StdResult, SubMsg, SubMsgResponse, Uint128, WasmMsg,
 };
-use cw_utils::parse_instantiate_response_data;
+use cw_utils::{parse_execute_response_data, parse_instantiate_response_data};
 
 use crate::{
     error::ContractError,
     msg::InstantiationData,
-    state::{candidates, AWAITING_INITIAL_RESPS, CONFIG},
+    state::{
+        candidates, removals, Member, PendingMember, AWAITING_INITIAL_RESPS, AWAITING_POOL, CONFIG,
+        EXECUTING_PROPOSAL, GENERIC_PROPOSALS, PENDING_MEMBER, PROPOSALS,
+    },
 };
+use distribution::msg::ExecMsg as DistributionExecMsg;
+use proposal::state::ProposalKind;
 use proxy::msg::InstantiateMsg as ProxyInstantiateMsg;
 
 use crate::state::members;
@@ -28,76 +30,91 @@ pub fn distribution_instantiated(
     let data = response.data.ok_or(ContractError::DataMissing)?;
     let response = parse_instantiate_response_data(&data)?;
     let initial_members: Vec<String> =
-        from_binary(&response.data.ok_or(ContractError::DataMissing)?)?;
+        from_json(&response.data.ok_or(ContractError::DataMissing)?)?;
 
     let mut config = CONFIG.load(deps.storage)?;
+    // the pool was paid on instantiation in the vote token assumed until distribution tells
+    let paid_in = config.vote_token.clone();
     config.distribution_contract = Addr::unchecked(response.contract_address);
+    let distribution_config =
+        distribution::state::CONFIG.query(&deps.querier, config.distribution_contract.clone())?;
+    config.vote_token = distribution_config.vote_token;
     CONFIG.save(deps.storage, &config)?;
 
-    let total_vote_tokens = deps
-        .querier
-        .query_balance(env.contract.address.to_string(), VOTE_DENOM)?;
-
-    let mut vote_tokens_to_distribute = total_vote_tokens.clone();
-
-    if config.initial_vote_token_distribution_part > Decimal::percent(0) {
-        vote_tokens_to_distribute.amount =
-            vote_tokens_to_distribute.amount * config.initial_vote_token_distribution_part
-    }
-
-    let mut balance_vote_tokens = Coin {
-        amount: total_vote_tokens.amount - vote_tokens_to_distribute.amount,
-        denom: VOTE_DENOM.to_string(),
-    };
-
-    let vote_tokens_per_member = coin(
-        vote_tokens_to_distribute.amount.u128() / initial_members.len() as u128,
-        VOTE_DENOM,
+    ensure!(
+        paid_in == config.vote_token
+            || paid_in
+                .balance(&deps.querier, &env.contract.address)?
+                .is_zero(),
+        ContractError::VoteTokenPoolDenomInvalid {
+            denom: config.vote_token.denom()
+        }
     );
 
-    // any remainders
-    balance_vote_tokens.amount += vote_tokens_to_distribute.amount
-        - vote_tokens_per_member.amount * Uint128::new(initial_members.len() as u128);
+    let total_vote_tokens = config
+        .vote_token
+        .balance(&deps.querier, &env.contract.address)?;
+
+    let mut resp = Response::new();
+
+    let vote_tokens_per_member = if !total_vote_tokens.is_zero() {
+        // whole pool goes to the distribution reserve, initial members get theirs minted from it
+        resp = resp.add_message(
+            config
+                .vote_token
+                .transfer(&config.distribution_contract, total_vote_tokens)?,
+        );
+
+        super::pool_share(&config, total_vote_tokens, initial_members.len())
+    } else if distribution_config.vote_token_minter {
+        config.initial_votes
+    } else {
+        // nothing to mint from until the admin funds the reserve
+        AWAITING_POOL.save(deps.storage, &Empty {})?;
+        Uint128::zero()
+    };
+    let vote_tokens_per_member = config.vote_token.coin(vote_tokens_per_member);
 
     let membership_contract = env.contract.address.to_string();
-    let msgs: Vec<_> = initial_members
-        .into_iter()
-        .map(|member| -> Result<_, ContractError> {
-            let addr = deps.api.addr_validate(&member)?;
-            let init_msg = ProxyInstantiateMsg {
-                owner: addr.to_string(),
-                distribution_contract: config.distribution_contract.to_string(),
-                membership_contract: membership_contract.clone(),
-            };
-            let msg = WasmMsg::Instantiate {
-                admin: Some(membership_contract.clone()),
-                code_id: config.proxy_code_id,
-                msg: to_binary(&init_msg)?,
-                funds: vec![vote_tokens_per_member.clone()],
-                label: format!("{} Proxy", addr),
-            };
-            let msg = SubMsg::reply_on_success(msg, super::INITIAL_PROXY_INSTANTIATION_REPLY_ID);
-
-            Ok(msg)
-        })
-        .collect::<Result<_, _>>()?;
+    let mut msgs = Vec::with_capacity(initial_members.len());
 
-    AWAITING_INITIAL_RESPS.save(deps.storage, &(msgs.len() as _))?;
-    let mut resp = Response::new().add_submessages(msgs);
+    for member in initial_members {
+        let addr = deps.api.addr_validate(&member)?;
 
-    if balance_vote_tokens.amount.u128() > 0 {
-        let bank_msg = BankMsg::Send {
-            to_address: config.distribution_contract.into_string(),
-            amount: vec![balance_vote_tokens],
+        let init_msg = ProxyInstantiateMsg {
+            owner: addr.to_string(),
+            distribution_contract: config.distribution_contract.to_string(),
+            membership_contract: membership_contract.clone(),
+            vote_token: config.vote_token.clone(),
+        };
+        let msg = WasmMsg::Instantiate {
+            admin: Some(membership_contract.clone()),
+            code_id: config.proxy_code_id,
+            msg: to_json_binary(&init_msg)?,
+            funds: vec![],
+            label: format!("{} Proxy", addr),
         };
-        resp = resp.add_message(bank_msg)
+        msgs.push(SubMsg::reply_on_success(
+            msg,
+            super::INITIAL_PROXY_INSTANTIATION_REPLY_ID,
+        ));
     }
 
-    Ok(resp)
+    AWAITING_INITIAL_RESPS.save(deps.storage, &(msgs.len() as _))?;
+    PENDING_MEMBER.save(
+        deps.storage,
+        &PendingMember {
+            proposal: None,
+            initial_votes: vote_tokens_per_member,
+        },
+    )?;
+
+    Ok(resp.add_submessages(msgs))
 }
 
 pub fn initial_proxy_instantiated(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     reply: Result<SubMsgResponse, String>,
 ) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
@@ -106,38 +123,44 @@ pub fn initial_proxy_instantiated(
     let addr = Addr::unchecked(response.contract_address);
 
     let owner = proxy::state::OWNER.query(&deps.querier, addr.clone())?;
-    members().save(deps.storage, &addr, &owner)?;
+    let member = save_member(deps.branch(), &env, &addr, owner)?;
+
+    let mut resp = Response::new();
+    if let Some(mint_msg) = initial_votes_msg(deps.as_ref(), &addr, &member)? {
+        resp = resp.add_message(mint_msg);
+    }
 
     let awaiting = AWAITING_INITIAL_RESPS.load(deps.storage)? - 1;
     if awaiting > 0 {
         AWAITING_INITIAL_RESPS.save(deps.storage, &awaiting)?;
 
-        let resp = Response::new().add_attribute("proxy_addr", addr);
+        let resp = resp.add_attribute("proxy_addr", addr);
         return Ok(resp);
     }
 
     let members: Vec<_> = members()
         .range(deps.storage, None, None, Order::Ascending)
         .map(|member| -> StdResult<_> {
-            let (member, owner) = member?;
+            let (proxy, member) = member?;
             let data = ProxyMemberData {
-                owner_addr: owner.into(),
-                proxy_addr: member.into(),
+                owner_addr: member.owner.into(),
+                proxy_addr: proxy.into(),
             };
             Ok(data)
         })
         .collect::<StdResult<_>>()?;
 
     let inst_data = InstantiationData { members };
-    let resp = Response::new()
+    let resp = resp
         .add_attribute("proxy addr", addr.as_str())
-        .set_data(to_binary(&inst_data)?);
+        .set_data(to_json_binary(&inst_data)?);
 
     Ok(resp)
 }
 
 pub fn proxy_instantiated(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     reply: Result<SubMsgResponse, String>,
 ) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
@@ -147,22 +170,27 @@ pub fn proxy_instantiated(
     let proxy_addr = Addr::unchecked(response.contract_address);
     let proxy_owner = proxy::state::OWNER.query(&deps.querier, proxy_addr.clone())?;
 
-    members().save(deps.storage, &proxy_addr, &proxy_owner)?;
+    let member = save_member(deps.branch(), &env, &proxy_addr, proxy_owner.clone())?;
 
     let member_data = ProxyMemberData {
         owner_addr: proxy_owner.to_string(),
         proxy_addr: proxy_addr.to_string(),
     };
 
-    let resp = Response::new()
+    let mut resp = Response::new();
+    if let Some(mint_msg) = initial_votes_msg(deps.as_ref(), &proxy_addr, &member)? {
+        resp = resp.add_message(mint_msg);
+    }
+
+    let resp = resp
         .add_attribute("proxy addr", proxy_addr.as_str())
-        .set_data(to_binary(&member_data)?);
+        .set_data(to_json_binary(&member_data)?);
 
     Ok(resp)
 }
 
 pub fn proposal_instantiated(
-    deps: DepsMut,
+    mut deps: DepsMut,
     reply: Result<SubMsgResponse, String>,
 ) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
@@ -174,15 +202,93 @@ pub fn proposal_instantiated(
 
     // new proposal new candidate
     candidates().save(deps.storage, &owner, &addr)?;
+    PROPOSALS.save(deps.storage, &addr, &Empty {})?;
 
     let data = ProposalMemberData {
         owner_addr: owner.into(),
         proposal_addr: addr.to_string(),
     };
 
+    let escrow_msg = escrow_msg(deps.branch(), &addr)?;
+
+    // proposer escrow is a yes vote, so it is the proposer's share of the joining fee
+    let config = CONFIG.load(deps.storage)?;
+    let shares_msg = DistributionExecMsg::AddRewardShares {
+        proposal: addr.to_string(),
+        voter_proxy: proposal::state::PROPOSER
+            .query(&deps.querier, addr.clone())?
+            .into_string(),
+        amount: proposal::state::TALLY
+            .query(&deps.querier, addr.clone())?
+            .yes,
+        height: proposal::state::START_HEIGHT.query(&deps.querier, addr.clone())?,
+    };
+    let shares_msg = super::distribution_msg(&config, &shares_msg, vec![])?;
+
+    let resp = Response::new()
+        .add_message(escrow_msg)
+        .add_message(shares_msg)
+        .add_attribute("proposal addr", addr.as_str())
+        .set_data(to_json_binary(&data)?);
+
+    Ok(resp)
+}
+
+pub fn generic_proposal_instantiated(
+    mut deps: DepsMut,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
+    let response = reply.map_err(StdError::generic_err)?;
+    let data = response.data.ok_or(ContractError::DataMissing)?;
+    let response = parse_instantiate_response_data(&data)?;
+    let addr = Addr::unchecked(response.contract_address);
+
+    GENERIC_PROPOSALS.save(deps.storage, &addr, &Empty {})?;
+    PROPOSALS.save(deps.storage, &addr, &Empty {})?;
+
+    let data = ProposalGenericData {
+        proposal_addr: addr.to_string(),
+    };
+
+    let escrow_msg = escrow_msg(deps.branch(), &addr)?;
+
+    let resp = Response::new()
+        .add_message(escrow_msg)
+        .add_attribute("proposal addr", addr.as_str())
+        .set_data(to_json_binary(&data)?);
+
+    Ok(resp)
+}
+
+pub fn removal_proposal_instantiated(
+    mut deps: DepsMut,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
+    let response = reply.map_err(StdError::generic_err)?;
+    let data = response.data.ok_or(ContractError::DataMissing)?;
+    let response = parse_instantiate_response_data(&data)?;
+    let addr = Addr::unchecked(response.contract_address);
+
+    let ProposalKind::Removal { member } =
+        proposal::state::KIND.query(&deps.querier, addr.clone())?
+    else {
+        return Err(ContractError::NotRemovalProposal);
+    };
+
+    removals().save(deps.storage, &member, &addr)?;
+    PROPOSALS.save(deps.storage, &addr, &Empty {})?;
+
+    let data = ProposalMemberData {
+        owner_addr: member.into(),
+        proposal_addr: addr.to_string(),
+    };
+
+    let escrow_msg = escrow_msg(deps.branch(), &addr)?;
+
     let resp = Response::new()
+        .add_message(escrow_msg)
         .add_attribute("proposal addr", addr.as_str())
-        .set_data(to_binary(&data)?);
+        .set_data(to_json_binary(&data)?);
 
     Ok(resp)
 }
@@ -194,14 +300,82 @@ pub fn proposal_passed(
 ) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
     let data = response.data.ok_or(ContractError::DataMissing)?;
-    let response = parse_instantiate_response_data(&data)?;
-    let proposal_addr = Addr::unchecked(response.contract_address);
-
-    let owner: Addr = proposal::state::OWNER.query(&deps.querier, proposal_addr.clone())?;
+    let response = parse_execute_response_data(&data)?;
+    let data: ProposalMemberData = from_json(response.data.ok_or(ContractError::DataMissing)?)?;
 
     let resp = Response::new()
-        .add_attribute("proposal_addr", proposal_addr.as_str())
-        .add_attribute("owner", owner.as_str());
+        .add_attribute("proposal_addr", data.proposal_addr)
+        .add_attribute("owner", data.owner_addr);
+
+    Ok(resp)
+}
+
+/// Reports a failed message of a passed generic proposal, only its own changes are reverted
+pub fn generic_proposal_msg_executed(
+    deps: DepsMut,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
+    let mut executing = EXECUTING_PROPOSAL.load(deps.storage)?;
+    executing.pending -= 1;
+    if executing.pending == 0 {
+        EXECUTING_PROPOSAL.remove(deps.storage);
+    } else {
+        EXECUTING_PROPOSAL.save(deps.storage, &executing)?;
+    }
+
+    let mut resp = Response::new();
+    if let Err(error) = reply {
+        let event = Event::new("generic_proposal_msg_failed")
+            .add_attribute("proposal", executing.proposal.as_str())
+            .add_attribute("error", error);
+        resp = resp.add_event(event);
+    }
 
     Ok(resp)
 }
+
+// proposals are instantiated without funds as cw20 vote tokens cannot be sent along,
+// proposer escrow follows once the address is known
+fn escrow_msg(deps: DepsMut, proposal: &Addr) -> StdResult<CosmosMsg> {
+    let config = CONFIG.load(deps.storage)?;
+    let escrow = proposal::state::TALLY
+        .query(&deps.querier, proposal.clone())?
+        .total();
+
+    let proposer = proposal::state::PROPOSER.query(&deps.querier, proposal.clone())?;
+    super::escrow_delegations(deps, &config, proposal, &proposer)?;
+
+    config.vote_token.transfer(proposal, escrow)
+}
+
+fn save_member(deps: DepsMut, env: &Env, proxy: &Addr, owner: Addr) -> StdResult<Member> {
+    let pending = PENDING_MEMBER.load(deps.storage)?;
+
+    let member = Member {
+        owner,
+        joined_height: env.block.height,
+        joined_time: env.block.time,
+        proposal: pending.proposal,
+        initial_votes: pending.initial_votes,
+    };
+
+    members().save(deps.storage, proxy, &member)?;
+
+    Ok(member)
+}
+
+// initial votes are minted into the proxy, which holds and votes with them for the owner
+fn initial_votes_msg(deps: Deps, proxy: &Addr, member: &Member) -> StdResult<Option<WasmMsg>> {
+    if member.initial_votes.amount.is_zero() {
+        return Ok(None);
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mint_msg = DistributionExecMsg::MintVoteTokens {
+        recipient: proxy.to_string(),
+        amount: member.initial_votes.amount,
+        holder: Some(member.owner.to_string()),
+    };
+
+    super::distribution_msg(&config, &mint_msg, vec![]).map(Some)
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
 
@@ -24,31 +24,72 @@ pub enum ContractError {
     #[error("vote tokens distribution part must be between [0-100]")]
     InitialisationInvalidVoteTokenDistributionPart,
 
+    #[error("voting period must not be zero")]
+    InitialisationInvalidVotingPeriod,
+
+    #[error("quorum must be between (0-100]")]
+    InitialisationInvalidQuorum,
+
+    #[error("threshold must be between [0-100)")]
+    InitialisationInvalidThreshold,
+
     #[error("vote tokens missing on initialization")]
     InitialisationVoteTokensMissing,
 
     #[error("Less vote tokens on initialization")]
     InitialisationLessVoteTokens,
 
+    #[error("Vote token pool must be paid in {denom}")]
+    VoteTokenPoolDenomInvalid { denom: String },
+
+    #[error("Vote token pool is already funded")]
+    PoolAlreadyFunded,
+
     #[error("Not enough new member vote tokens")]
     NotEnoughNewMemberVoteTokens,
 
     #[error("not a proposed member")]
     NotProposedMember,
 
-    #[error("joining fee must {denom}(s)")]
+    #[error("not an open generic proposal")]
+    NotGenericProposal,
+
+    #[error("not an open removal proposal")]
+    NotRemovalProposal,
+
+    #[error("not a member")]
+    NotAMember,
+
+    #[error("joining fee must be non empty and in {denom}")]
     JoiningFeeDenomInvalid { denom: String },
 
+    #[error("vote token price must be a non zero amount of {denom}(s)")]
+    VoteTokenPriceInvalid { denom: String },
+
     #[error("Missing expected data")]
     DataMissing,
 
     #[error("Cannot propose a member")]
     AlreadyAMember,
 
-    #[error("Member proxy mistmatch")]
-    MemberProxyMismatch,
+    #[error("Escrow exceeds the voting power of {power}")]
+    InsufficientVotingPower { power: Uint128 },
+
+    #[error("Cannot delegate to oneself")]
+    SelfDelegation,
+
+    #[error("Only {delegated} vote tokens are delegated")]
+    InsufficientDelegation { delegated: Uint128 },
 
     #[error("Existing proposal voting in progress")]
     ExistingProposalInProgress,
 
+    #[error("{0}")]
+    Semver(#[from] semver::Error),
+
+    #[error("Cannot migrate from contract {name}")]
+    MigrationInvalidContract { name: String },
+
+    #[error("Cannot migrate from newer version {version}")]
+    MigrationDowngrade { version: String },
 }
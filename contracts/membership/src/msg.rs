@@ -1,18 +1,30 @@
-use common::msg::ProxyMemberData;
+use common::{msg::ProxyMemberData, vote_token::VoteToken};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Coin, Decimal};
+use cosmwasm_std::{Coin, Decimal, Uint128};
+use cw_utils::Duration;
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub initial_vote_token_distribution_part: Decimal,
     pub vote_token_price: Coin,
-    pub joining_fee: Coin,
+    pub reward_denoms: Vec<String>,
+    /// candidates pay any one of them
+    pub joining_fee: Vec<Coin>,
+    pub initial_votes: Uint128,
+    pub owner_addr: String,
+    pub voting_period: Duration,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
     pub proxy_code_id: u64,
     pub proposal_code_id: u64,
     pub distribution_code_id: u64,
     pub initial_members: Vec<String>,
+    pub vote_token: VoteToken,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct InstantiationData {
     pub members: Vec<ProxyMemberData>,
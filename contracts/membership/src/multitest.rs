@@ -2,19 +2,25 @@ use std::string::ParseError;
 
 use crate::error::ContractError;
 use crate::msg::{InstantiateMsg, InstantiationData};
-use crate::state::{Config, CONFIG};
-use crate::{execute, instantiate, query, reply};
+use crate::state::{Config, ExecutingProposal, CONFIG, EXECUTING_PROPOSAL};
+use crate::{execute, instantiate, migrate, query, reply};
 use anyhow::Result as AnyResult;
-use common::msg::membership::{ExecMsg, IsMemberResp, QueryMsg};
+use common::msg::membership::{
+    ChildKind, ConfigUpdate, DelegationsResp, ExecMsg, IsMemberResp, IsProposedMemberResp,
+    ListCandidatesResp, ListMembersResp, MemberCountResp, MemberProfileResp, OwnerProxyResp,
+    QueryMsg, SupplyInvariantResp, TotalPowerAtHeightResp, VotingPowerAtHeightResp,
+};
 use common::msg::{ProposalMemberData, ProxyMemberData};
-use cosmwasm_std::{from_binary, to_binary, Addr, Coin, Decimal, WasmMsg};
-use cw_multi_test::{App, ContractWrapper, Executor};
+use common::multitest::App;
+use cosmwasm_std::{from_json, to_json_binary, Addr, Coin, WasmMsg};
+use cw_multi_test::{ContractWrapper, Executor};
 use cw_utils::{parse_execute_response_data, parse_instantiate_response_data};
 
-use distribution::multitest::CodeId as DistributionId;
-use proposal::multitest::{CodeId as ProposalId, Contract as ProposalContract};
-use proxy::multitest::{CodeId as ProxyId, Contract as ProxyContract};
+use proposal::multitest::Contract as ProposalContract;
+use proxy::multitest::Contract as ProxyContract;
 
+#[cfg(test)]
+mod suite;
 #[cfg(test)]
 mod tests;
 
@@ -22,39 +28,21 @@ pub struct CodeId(u64);
 
 impl CodeId {
     pub fn store_code(app: &mut App) -> Self {
-        let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+        let contract = ContractWrapper::new_with_empty(execute, instantiate, query)
+            .with_reply_empty(reply)
+            .with_migrate_empty(migrate);
         CodeId(app.store_code(Box::new(contract)))
     }
 
-    #[allow(clippy::too_many_arguments)]
+    #[track_caller]
     pub fn instantiate(
         self,
         app: &mut App,
         sender: &Addr,
-        initial_vote_token_distribution_part: Decimal,
-        vote_token_price: Coin,
-        joining_fee: Coin,
-        proxy_code_id: ProxyId,
-        proposal_code_id: ProposalId,
-        distribution_code_id: DistributionId,
-        initial_members: &[&str],
-        label: &str,
+        msg: &InstantiateMsg,
         funds: &[Coin],
     ) -> AnyResult<(Contract, InstantiationData)> {
-        Contract::instantiate(
-            app,
-            self,
-            sender,
-            initial_vote_token_distribution_part,
-            vote_token_price,
-            joining_fee,
-            proxy_code_id,
-            proposal_code_id,
-            distribution_code_id,
-            initial_members,
-            label,
-            funds,
-        )
+        Contract::instantiate(app, self, sender, msg, funds)
     }
 }
 
@@ -66,38 +54,20 @@ impl Contract {
         &self.0
     }
 
-    #[allow(clippy::too_many_arguments)]
-    // #[track_caller]
+    #[track_caller]
     pub fn instantiate(
         app: &mut App,
         code_id: CodeId,
         sender: &Addr,
-        initial_vote_token_distribution_part: Decimal,
-        vote_token_price: Coin,
-        joining_fee: Coin,
-        proxy_code_id: ProxyId,
-        proposal_code_id: ProposalId,
-        distribution_code_id: DistributionId,
-        initial_members: &[&str],
-        label: &str,
+        msg: &InstantiateMsg,
         funds: &[Coin],
     ) -> AnyResult<(Contract, InstantiationData)> {
-        let msg = InstantiateMsg {
-            vote_token_price,
-            joining_fee,
-            initial_vote_token_distribution_part,
-            proxy_code_id: proxy_code_id.into(),
-            proposal_code_id: proposal_code_id.into(),
-            distribution_code_id: distribution_code_id.into(),
-            initial_members: initial_members.iter().map(|s| s.to_string()).collect(),
-        };
-
         let msg = WasmMsg::Instantiate {
             admin: Some(sender.to_string()),
             code_id: code_id.0,
-            msg: to_binary(&msg)?,
+            msg: to_json_binary(msg)?,
             funds: funds.to_vec(),
-            label: label.into(),
+            label: "Membership".to_owned(),
         };
 
         let res = app.execute(sender.clone(), msg.into())?;
@@ -105,7 +75,7 @@ impl Contract {
         let data = parse_instantiate_response_data(res.data.unwrap_or_default().as_slice())?;
 
         let contract = Self(Addr::unchecked(data.contract_address));
-        let data = from_binary(&data.data.unwrap_or_default())?;
+        let data = from_json(data.data.unwrap_or_default())?;
         Ok((contract, data))
     }
 
@@ -119,7 +89,155 @@ impl Contract {
             .map_err(Into::into)
     }
 
+    pub fn is_proposed_member(&self, app: &App, addr: &str) -> AnyResult<IsProposedMemberResp> {
+        let query = QueryMsg::IsProposedMember {
+            addr: addr.to_owned(),
+        };
+
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &query)
+            .map_err(Into::into)
+    }
+
+    pub fn member_by_proxy(&self, app: &App, proxy: &Addr) -> AnyResult<OwnerProxyResp> {
+        let query = QueryMsg::MemberByProxy {
+            proxy: proxy.to_string(),
+        };
+
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &query)
+            .map_err(Into::into)
+    }
+
+    pub fn member_profile(&self, app: &App, owner: &Addr) -> AnyResult<MemberProfileResp> {
+        let query = QueryMsg::MemberProfile {
+            owner: owner.to_string(),
+        };
+
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &query)
+            .map_err(Into::into)
+    }
+
+    pub fn list_members(
+        &self,
+        app: &App,
+        start_after: Option<&Addr>,
+        limit: Option<u32>,
+    ) -> AnyResult<ListMembersResp> {
+        let query = QueryMsg::ListMembers {
+            start_after: start_after.map(Addr::to_string),
+            limit,
+        };
+
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &query)
+            .map_err(Into::into)
+    }
+
+    pub fn list_candidates(
+        &self,
+        app: &App,
+        start_after: Option<&Addr>,
+        limit: Option<u32>,
+    ) -> AnyResult<ListCandidatesResp> {
+        let query = QueryMsg::ListCandidates {
+            start_after: start_after.map(Addr::to_string),
+            limit,
+        };
+
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &query)
+            .map_err(Into::into)
+    }
+
+    pub fn member_count(&self, app: &App) -> AnyResult<MemberCountResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::MemberCount {})
+            .map_err(Into::into)
+    }
+
+    pub fn supply_invariant(&self, app: &App) -> AnyResult<SupplyInvariantResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::SupplyInvariant {})
+            .map_err(Into::into)
+    }
+
+    pub fn voting_power_at_height(
+        &self,
+        app: &App,
+        address: &Addr,
+        height: Option<u64>,
+    ) -> AnyResult<VotingPowerAtHeightResp> {
+        let query = QueryMsg::VotingPowerAtHeight {
+            address: address.to_string(),
+            height,
+        };
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &query)
+            .map_err(Into::into)
+    }
+
+    pub fn total_power_at_height(
+        &self,
+        app: &App,
+        height: Option<u64>,
+    ) -> AnyResult<TotalPowerAtHeightResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::TotalPowerAtHeight { height })
+            .map_err(Into::into)
+    }
+
+    pub fn delegations(&self, app: &App, owner: &Addr) -> AnyResult<DelegationsResp> {
+        let query = QueryMsg::Delegations {
+            owner: owner.to_string(),
+        };
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &query)
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn migrate_children(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        code_id: u64,
+        kind: ChildKind,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::MigrateChildren {
+            code_id,
+            kind,
+            start_after,
+            limit,
+        };
+        app.execute_contract(sender.clone(), self.0.clone(), &msg, &[])?;
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_config(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        config: ConfigUpdate,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::UpdateConfig { config };
+        app.execute_contract(sender.clone(), self.0.clone(), &msg, &[])?;
+        Ok(())
+    }
+
     pub fn load_config(&self, app: &App) -> Config {
         CONFIG.query(&app.wrap(), self.addr().clone()).unwrap()
     }
+
+    #[track_caller]
+    pub fn executing_proposal(&self, app: &App) -> Option<ExecutingProposal> {
+        app.wrap()
+            .query_wasm_raw(self.addr(), EXECUTING_PROPOSAL.as_slice())
+            .unwrap()
+            .map(|raw| from_json(raw).unwrap())
+    }
 }
@@ -0,0 +1,323 @@
+use anyhow::Result as AnyResult;
+use common::keys::{ATOM, VOTE_DENOM};
+use common::msg::membership::ConfigUpdate;
+use common::multitest::{App, TokenFactory};
+use common::vote_token::{Cw20Source, VoteAsset, VoteToken};
+use cosmwasm_std::{coin, coins, Addr, BlockInfo, Coin, Decimal, Uint128};
+use cw20::Cw20Coin;
+use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+use cw_multi_test::{next_block, ContractWrapper, Executor};
+use cw_utils::Duration;
+
+use super::{CodeId as MembershipId, Contract as MembershipContract};
+use crate::msg::InstantiateMsg;
+use distribution::multitest::{
+    CodeId as DistributionId, Contract as DistributionContract, TOKEN_FACTORY,
+};
+use proposal::msg::{ProposalStatus, VoteOption};
+use proposal::multitest::{CodeId as ProposalId, Contract as ProposalContract};
+use proxy::multitest::{CodeId as ProxyId, Contract as ProxyContract};
+
+pub const ADMIN: &str = "admin";
+
+enum VoteTokenSource {
+    /// `VOTE` pool paid by the admin on instantiation, or minted by distribution when it is
+    /// built with the token factory
+    Native,
+    /// cw20 token instantiated and minted by distribution
+    Cw20Minted,
+    /// existing cw20 token of which the admin holds `balance`
+    Cw20Held { balance: u128 },
+}
+
+pub struct SuiteBuilder {
+    members: Vec<String>,
+    funds: Vec<(Addr, Vec<Coin>)>,
+    pool: u128,
+    pool_part: Decimal,
+    vote_token_price: Coin,
+    joining_fee: Vec<Coin>,
+    initial_votes: u128,
+    reward_denoms: Vec<String>,
+    vote_token: VoteTokenSource,
+}
+
+impl SuiteBuilder {
+    pub fn new() -> Self {
+        Self {
+            members: vec!["alice".to_owned(), "bob".to_owned(), "charlie".to_owned()],
+            funds: vec![],
+            pool: 100,
+            pool_part: Decimal::percent(30),
+            vote_token_price: coin(5, ATOM),
+            joining_fee: coins(30, ATOM),
+            initial_votes: 10,
+            reward_denoms: vec![ATOM.to_owned()],
+            vote_token: VoteTokenSource::Native,
+        }
+    }
+
+    pub fn with_members(mut self, members: &[&str]) -> Self {
+        self.members = members.iter().map(|member| member.to_string()).collect();
+        self
+    }
+
+    pub fn with_funds(mut self, addr: &str, funds: &[Coin]) -> Self {
+        self.funds.push((Addr::unchecked(addr), funds.to_vec()));
+        self
+    }
+
+    /// Percentage of the `VOTE` pool split among the initial members
+    pub fn with_pool_part(mut self, percent: u64) -> Self {
+        self.pool_part = Decimal::percent(percent);
+        self
+    }
+
+    pub fn with_vote_token_price(mut self, price: Coin) -> Self {
+        self.vote_token_price = price;
+        self
+    }
+
+    pub fn with_joining_fee(mut self, fee: &[Coin]) -> Self {
+        self.joining_fee = fee.to_vec();
+        self
+    }
+
+    pub fn with_initial_votes(mut self, votes: u128) -> Self {
+        self.initial_votes = votes;
+        self
+    }
+
+    pub fn with_reward_denoms(mut self, denoms: &[&str]) -> Self {
+        self.reward_denoms = denoms.iter().map(|denom| denom.to_string()).collect();
+        self
+    }
+
+    pub fn with_cw20_vote_token(mut self) -> Self {
+        self.vote_token = VoteTokenSource::Cw20Minted;
+        self
+    }
+
+    /// Existing cw20 vote token, the admin funds the pool out of `balance` after instantiation
+    pub fn with_cw20_vote_token_held_by_admin(mut self, balance: u128) -> Self {
+        self.vote_token = VoteTokenSource::Cw20Held { balance };
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self) -> Suite {
+        self.try_build().unwrap()
+    }
+
+    pub fn try_build(self) -> AnyResult<Suite> {
+        let admin = Addr::unchecked(ADMIN);
+
+        let mut funds = self.funds;
+        if let (VoteTokenSource::Native, false) = (&self.vote_token, TOKEN_FACTORY) {
+            let pool = coin(self.pool, VOTE_DENOM);
+            match funds.iter_mut().find(|(addr, _)| *addr == admin) {
+                Some((_, admin_funds)) => admin_funds.push(pool),
+                None => funds.push((admin.clone(), vec![pool])),
+            }
+        }
+
+        let mut app = TokenFactory::app_with_balances(funds);
+
+        let cw20_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        )));
+        let proxy_id = ProxyId::store_code(&mut app);
+        let proposal_id = ProposalId::store_code(&mut app);
+        let distribution_id = DistributionId::store_code(&mut app);
+        let membership_id = MembershipId::store_code(&mut app);
+
+        // a minted `VOTE` pool cannot be paid, the initial members get their share of it as
+        // initial votes and the configured ones apply to members joining later on
+        let mut initial_votes = self.initial_votes;
+        let (vote_token, pool) = match self.vote_token {
+            VoteTokenSource::Native if TOKEN_FACTORY => {
+                let mut distributed = Uint128::new(self.pool);
+                if !self.pool_part.is_zero() {
+                    distributed = distributed * self.pool_part;
+                }
+                initial_votes = distributed.u128() / self.members.len() as u128;
+                (VoteToken::Native {}, vec![])
+            }
+            VoteTokenSource::Native => (VoteToken::Native {}, coins(self.pool, VOTE_DENOM)),
+            VoteTokenSource::Cw20Minted => (VoteToken::Cw20(Cw20Source::CodeId(cw20_id)), vec![]),
+            VoteTokenSource::Cw20Held { balance } => {
+                let msg = Cw20InstantiateMsg {
+                    name: "Vote Token".to_owned(),
+                    symbol: "VOTE".to_owned(),
+                    decimals: 0,
+                    initial_balances: vec![Cw20Coin {
+                        address: admin.to_string(),
+                        amount: Uint128::new(balance),
+                    }],
+                    mint: None,
+                    marketing: None,
+                };
+                let token = app.instantiate_contract(
+                    cw20_id,
+                    admin.clone(),
+                    &msg,
+                    &[],
+                    "Vote Token",
+                    None,
+                )?;
+                (
+                    VoteToken::Cw20(Cw20Source::Addr(token.into_string())),
+                    vec![],
+                )
+            }
+        };
+
+        let msg = InstantiateMsg {
+            initial_vote_token_distribution_part: self.pool_part,
+            vote_token_price: self.vote_token_price,
+            reward_denoms: self.reward_denoms,
+            joining_fee: self.joining_fee,
+            initial_votes: Uint128::new(initial_votes),
+            owner_addr: admin.to_string(),
+            voting_period: Duration::Height(100),
+            quorum: Decimal::percent(50),
+            threshold: Decimal::percent(50),
+            proxy_code_id: proxy_id.into(),
+            proposal_code_id: proposal_id.into(),
+            distribution_code_id: distribution_id.into(),
+            initial_members: self.members,
+            vote_token,
+        };
+        let genesis = app.block_info();
+        let (membership, _) = membership_id.instantiate(&mut app, &admin, &msg, &pool)?;
+        if initial_votes != self.initial_votes {
+            let config = ConfigUpdate {
+                initial_votes: Some(Uint128::new(self.initial_votes)),
+                ..ConfigUpdate::default()
+            };
+            membership.update_config(&mut app, &admin, config)?;
+        }
+
+        let config = membership.load_config(&app);
+        let mut suite = Suite {
+            app,
+            admin,
+            membership,
+            distribution: DistributionContract::from_addr(config.distribution_contract),
+            vote_token: config.vote_token,
+            genesis,
+        };
+        suite.next_block();
+
+        Ok(suite)
+    }
+}
+
+pub struct Suite {
+    pub app: App,
+    pub admin: Addr,
+    pub membership: MembershipContract,
+    pub distribution: DistributionContract,
+    pub vote_token: VoteAsset,
+    /// block the initial members joined in
+    pub genesis: BlockInfo,
+}
+
+impl Suite {
+    /// Proposals count voting power from before the block they start in, vote tokens received
+    /// in this block count for proposals made from the next one on
+    pub fn next_block(&mut self) {
+        self.app.update_block(next_block);
+    }
+
+    pub fn advance_blocks(&mut self, blocks: u64) {
+        self.app.update_block(|block| block.height += blocks);
+    }
+
+    /// Proxy of a current member
+    #[track_caller]
+    pub fn proxy(&self, owner: &Addr) -> ProxyContract {
+        let profile = self.membership.member_profile(&self.app, owner).unwrap();
+        ProxyContract::from_addr(Addr::unchecked(profile.proxy))
+    }
+
+    pub fn vote_coin(&self, amount: u128) -> Coin {
+        self.vote_token.coin(Uint128::new(amount))
+    }
+
+    #[track_caller]
+    pub fn vote_balance(&self, addr: &Addr) -> u128 {
+        self.vote_token
+            .balance(&self.app.wrap(), addr)
+            .unwrap()
+            .u128()
+    }
+
+    #[track_caller]
+    pub fn propose_member(
+        &mut self,
+        proposer: &Addr,
+        escrow: u128,
+        candidate: &Addr,
+    ) -> ProposalContract {
+        let data = self
+            .proxy(proposer)
+            .propose_member(&mut self.app, proposer, escrow, candidate)
+            .unwrap()
+            .unwrap();
+        ProposalContract::from_addr(Addr::unchecked(data.proposal_addr))
+    }
+
+    /// Member proposal of `candidate` passed by the proposer escrow and the yes `votes`
+    #[track_caller]
+    pub fn propose_and_pass(
+        &mut self,
+        proposer: &Addr,
+        escrow: u128,
+        candidate: &Addr,
+        votes: &[(&Addr, u128)],
+    ) -> ProposalContract {
+        let proposal = self.propose_member(proposer, escrow, candidate);
+        for (voter, amount) in votes {
+            self.proxy(voter)
+                .vote(
+                    &mut self.app,
+                    voter,
+                    proposal.addr(),
+                    VoteOption::Yes,
+                    *amount,
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            proposal.status(&self.app).unwrap().status,
+            ProposalStatus::Passed
+        );
+        proposal
+    }
+
+    /// Passes a member proposal of `candidate` and joins it paying `fee`, returns the new proxy
+    #[track_caller]
+    pub fn admit(
+        &mut self,
+        proposer: &Addr,
+        escrow: u128,
+        candidate: &Addr,
+        votes: &[(&Addr, u128)],
+        fee: &[Coin],
+    ) -> ProxyContract {
+        let proposal = self.propose_and_pass(proposer, escrow, candidate, votes);
+        let data = proposal
+            .join(&mut self.app, candidate, fee)
+            .unwrap()
+            .unwrap();
+        ProxyContract::from_addr(Addr::unchecked(data.proxy_addr))
+    }
+
+    #[track_caller]
+    pub fn assert_supply_invariant(&self) {
+        assert!(self.membership.supply_invariant(&self.app).unwrap().holds);
+    }
+}
@@ -1,419 +1,2426 @@
-use std::collections::HashMap;
-
-use common::keys::{ATOM, VOTE_DENOM};
-use common::msg::{ProposalMemberData, WithdrawableResp};
-use cosmwasm_std::{coin, coins, Addr, Decimal};
-use cw_multi_test::App;
-
-use super::CodeId as MembershipId;
-use distribution::multitest::{CodeId as DistributionId, Contract as DistributionContract};
+use anyhow::Result as AnyResult;
+use common::keys::ATOM;
+use common::msg::membership::{
+    CandidateResp, ChildKind, ConfigUpdate, DelegationResp, ExecMsg as MembershipExecMsg, InfoResp,
+    MemberProfileResp, OwnerProxyResp, QueryMsg as MembershipQueryMsg, TotalPowerAtHeightResp,
+    VotingPowerAtHeightResp,
+};
+use common::msg::{BuyVoteTokensResp, WithdrawableResp};
+use common::multitest::App;
+use common::vote_token::VoteAsset;
+use cosmwasm_std::{
+    coin, coins, to_json_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_multi_test::{AppResponse, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+use serde::Serialize;
+
+use super::suite::SuiteBuilder;
+use crate::error::ContractError;
+use distribution::error::ContractError as DistributionContractError;
+use distribution::multitest::{CodeId as DistributionId, TOKEN_FACTORY};
+use distribution::state::REWARD_SHARES;
+use proposal::error::ContractError as ProposalContractError;
+use proposal::msg::{ExecMsg as ProposalExecMsg, ProposalStatus, VoteOption};
 use proposal::multitest::{CodeId as ProposalId, Contract as ProposalContract};
 use proxy::multitest::{CodeId as ProxyId, Contract as ProxyContract};
 
 #[test]
 fn sample_member_vote_flow_from_exercise() {
-    let admin = Addr::unchecked("admin");
     let member1 = Addr::unchecked("member1");
     let member2 = Addr::unchecked("member2");
     let member3 = Addr::unchecked("member3");
-    let members = [member1.as_str(), member2.as_str(), member3.as_str()];
     let candidate = Addr::unchecked("candidate");
 
-    let mut app = App::new(|router, _api, storage| {
-        router
-            .bank
-            .init_balance(storage, &admin, coins(100, VOTE_DENOM))
-            .unwrap();
-
-        router
-            .bank
-            .init_balance(storage, &candidate, coins(100, ATOM))
-            .unwrap();
-    });
-
-    let proxy_id = ProxyId::store_code(&mut app);
-    let proposal_id = ProposalId::store_code(&mut app);
-    let distribution_id = DistributionId::store_code(&mut app);
-    let membership_id = MembershipId::store_code(&mut app);
-
-    let (membership, data) = membership_id
-        .instantiate(
-            &mut app,
-            &admin,
-            Decimal::percent(19),
-            coin(5, ATOM),
-            coin(30, ATOM),
-            proxy_id,
-            proposal_id,
-            distribution_id,
-            &members,
-            "Membership",
-            &coins(100, VOTE_DENOM),
-        )
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["member1", "member2", "member3"])
+        .with_pool_part(19)
+        .with_funds("candidate", &coins(100, ATOM))
+        .build();
+
+    let member1_proxy = suite.proxy(&member1);
+    let member2_proxy = suite.proxy(&member2);
+    let member3_proxy = suite.proxy(&member3);
+
+    for proxy in [&member1_proxy, &member2_proxy, &member3_proxy] {
+        assert!(
+            suite
+                .membership
+                .is_member(&suite.app, proxy.addr().as_str())
+                .unwrap()
+                .ok
+        );
+        assert_eq!(suite.vote_balance(proxy.addr()), 6);
+    }
+
+    let proposal_data = member1_proxy
+        .propose_member(&mut suite.app, &member1, 5, &candidate)
+        .unwrap()
         .unwrap();
 
-    let membership_config = membership.load_config(&app);
-    let distribution_contract =
-        DistributionContract::from_addr(membership_config.distribution_contract);
+    assert_eq!(proposal_data.owner_addr, candidate.to_string());
 
-    let proxies: HashMap<_, _> = data
-        .members
-        .into_iter()
-        .map(|member| {
-            (
-                member.owner_addr,
-                ProxyContract::from_addr(Addr::unchecked(member.proxy_addr)),
-            )
-        })
-        .collect();
+    let candidate_proposal =
+        ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
+    assert_eq!(suite.vote_balance(candidate_proposal.addr()), 5);
+
+    member2_proxy
+        .vote(
+            &mut suite.app,
+            &member2,
+            candidate_proposal.addr(),
+            VoteOption::Yes,
+            3,
+        )
+        .unwrap();
+    assert_eq!(suite.vote_balance(candidate_proposal.addr()), 8);
+
+    member3_proxy
+        .vote(
+            &mut suite.app,
+            &member3,
+            candidate_proposal.addr(),
+            VoteOption::Yes,
+            2,
+        )
+        .unwrap();
+    assert_eq!(suite.vote_balance(candidate_proposal.addr()), 10);
 
-    assert_eq!(proxies.len(), 3);
-    let member1_proxy = proxies.get(members[0]).unwrap();
-    let member2_proxy = proxies.get(members[1]).unwrap();
-    let member3_proxy = proxies.get(members[2]).unwrap();
+    let candidate_proxy_data = candidate_proposal
+        .join(&mut suite.app, &candidate, &coins(100, ATOM))
+        .unwrap()
+        .unwrap();
+
+    let candidate_now_member_proxy =
+        ProxyContract::from_addr(Addr::unchecked(candidate_proxy_data.proxy_addr));
 
     assert!(
-        membership
-            .is_member(&app, member1_proxy.addr().as_str())
-            .unwrap()
-            .ok
-    );
-    assert!(
-        membership
-            .is_member(&app, member2_proxy.addr().as_str())
+        suite
+            .membership
+            .is_member(&suite.app, candidate_now_member_proxy.addr().as_str())
             .unwrap()
             .ok
     );
+    assert_eq!(suite.vote_balance(candidate_now_member_proxy.addr()), 10);
+
+    for (proxy, reward) in [
+        (&member1_proxy, 50),
+        (&member2_proxy, 30),
+        (&member3_proxy, 20),
+    ] {
+        assert_eq!(
+            proxy.withdrawable(&suite.app).unwrap(),
+            WithdrawableResp {
+                funds: vec![coin(reward, ATOM)]
+            }
+        );
+    }
+
+    member1_proxy
+        .withdraw(&mut suite.app, &member1, None)
+        .unwrap();
 
     assert_eq!(
-        app.wrap().query_balance(&member1, VOTE_DENOM).unwrap(),
-        coin(6, VOTE_DENOM),
+        suite.app.wrap().query_balance(&member1, ATOM).unwrap(),
+        coin(50, ATOM),
     );
-
     assert_eq!(
-        app.wrap().query_balance(&member2, VOTE_DENOM).unwrap(),
-        coin(6, VOTE_DENOM),
+        member1_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp { funds: vec![] }
     );
     assert_eq!(
-        app.wrap().query_balance(&member3, VOTE_DENOM).unwrap(),
-        coin(6, VOTE_DENOM),
+        suite
+            .app
+            .wrap()
+            .query_balance(suite.distribution.addr(), ATOM)
+            .unwrap(),
+        coin(50, ATOM),
     );
 
-    let proposal_data = member1_proxy
-        .propose_member(&mut app, &member1, &coins(5, VOTE_DENOM), &candidate)
+    let bought = member2_proxy
+        .buy_vote_tokens(&mut suite.app, &member2, None)
         .unwrap()
         .unwrap();
 
-    assert_eq!(proposal_data.owner_addr, candidate.to_string());
-
-    let candidate_proposal =
-        ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
+    assert_eq!(
+        bought,
+        BuyVoteTokensResp {
+            vote_tokens: suite.vote_coin(6),
+            change: coin(0, ATOM),
+        }
+    );
 
+    assert_eq!(suite.vote_balance(member2_proxy.addr()), 9);
+    assert_eq!(
+        member2_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp { funds: vec![] }
+    );
     assert_eq!(
-        app.wrap()
-            .query_balance(candidate_proposal.addr(), VOTE_DENOM)
+        suite
+            .app
+            .wrap()
+            .query_balance(suite.distribution.addr(), ATOM)
             .unwrap(),
-        coin(5, VOTE_DENOM),
+        coin(20, ATOM),
+    );
+    assert_eq!(
+        suite.app.wrap().query_balance(&suite.admin, ATOM).unwrap(),
+        coin(30, ATOM),
     );
 
-    candidate_proposal
-        .vote(&mut app, &member2, &coins(3, VOTE_DENOM))
+    assert_eq!(
+        suite
+            .distribution
+            .total_vote_tokens_in_circulation(&suite.app),
+        suite.vote_coin(24)
+    );
+    suite.assert_supply_invariant();
+    // a token factory distribution mints what it pays out instead of keeping a reserve
+    let reserve = if TOKEN_FACTORY { 0 } else { 76 };
+    assert_eq!(suite.vote_balance(suite.distribution.addr()), reserve);
+
+    let err = member3_proxy
+        .withdraw(&mut suite.app, &member3, Some(coins(25, ATOM)))
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast().unwrap(),
+        DistributionContractError::InsufficientRewards { available } if available == coin(20, ATOM)
+    ));
+
+    member3_proxy
+        .withdraw(&mut suite.app, &member3, Some(coins(15, ATOM)))
         .unwrap();
 
     assert_eq!(
-        app.wrap()
-            .query_balance(candidate_proposal.addr(), VOTE_DENOM)
+        suite.app.wrap().query_balance(&member3, ATOM).unwrap(),
+        coin(15, ATOM),
+    );
+    assert_eq!(
+        member3_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp {
+            funds: vec![coin(5, ATOM)]
+        }
+    );
+    assert_eq!(
+        suite
+            .app
+            .wrap()
+            .query_balance(member3_proxy.addr(), ATOM)
             .unwrap(),
-        coin(8, VOTE_DENOM),
+        coin(0, ATOM),
     );
+}
 
-    candidate_proposal
-        .vote(&mut app, &member3, &coins(2, VOTE_DENOM))
+#[test]
+fn member_vote_flow_with_rewards_and_vote_tokens_buy() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let charlie = Addr::unchecked("charlie");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob"])
+        .with_pool_part(10)
+        .with_initial_votes(6)
+        .with_funds("charlie", &coins(30, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+
+    for proxy in [&alice_proxy, &bob_proxy] {
+        assert!(
+            suite
+                .membership
+                .is_member(&suite.app, proxy.addr().as_str())
+                .unwrap()
+                .ok
+        );
+        assert_eq!(suite.vote_balance(proxy.addr()), 5);
+    }
+
+    let proposal_data = alice_proxy
+        .propose_member(&mut suite.app, &alice, 3, &charlie)
+        .unwrap()
         .unwrap();
 
-    assert_eq!(
-        app.wrap()
-            .query_balance(candidate_proposal.addr(), VOTE_DENOM)
-            .unwrap(),
-        coin(10, VOTE_DENOM),
-    );
+    assert_eq!(proposal_data.owner_addr, charlie.to_string());
 
-    let candidate_proxy_data = candidate_proposal
-        .join(&mut app, &candidate, &coins(100, ATOM))
+    let charlie_proposal =
+        ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
+    assert_eq!(suite.vote_balance(charlie_proposal.addr()), 3);
+
+    bob_proxy
+        .vote(
+            &mut suite.app,
+            &bob,
+            charlie_proposal.addr(),
+            VoteOption::Yes,
+            3,
+        )
+        .unwrap();
+    assert_eq!(suite.vote_balance(charlie_proposal.addr()), 6);
+
+    let charlie_proxy_data = charlie_proposal
+        .join(&mut suite.app, &charlie, &coins(30, ATOM))
         .unwrap()
         .unwrap();
 
-    let candidate_now_member_proxy =
-        ProxyContract::from_addr(Addr::unchecked(candidate_proxy_data.proxy_addr));
+    let charlie_proxy = ProxyContract::from_addr(Addr::unchecked(charlie_proxy_data.proxy_addr));
 
     assert!(
-        membership
-            .is_member(&app, candidate_now_member_proxy.addr().as_str())
+        suite
+            .membership
+            .is_member(&suite.app, charlie_proxy.addr().as_str())
             .unwrap()
             .ok
     );
+    assert_eq!(suite.vote_balance(charlie_proxy.addr()), 6);
+
+    for proxy in [&alice_proxy, &bob_proxy] {
+        assert_eq!(
+            proxy.withdrawable(&suite.app).unwrap(),
+            WithdrawableResp {
+                funds: vec![coin(15, ATOM)]
+            }
+        );
+    }
+
+    alice_proxy.withdraw(&mut suite.app, &alice, None).unwrap();
 
     assert_eq!(
-        app.wrap()
-            .query_balance(candidate.as_str(), VOTE_DENOM)
-            .unwrap(),
-        coin(10, VOTE_DENOM),
+        suite.app.wrap().query_balance(&alice, ATOM).unwrap(),
+        coin(15, ATOM),
     );
-
     assert_eq!(
-        member1_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp {
-            funds: Some(coin(50, ATOM))
-        }
+        alice_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp { funds: vec![] }
     );
-
     assert_eq!(
-        member2_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp {
-            funds: Some(coin(30, ATOM))
-        }
+        suite
+            .app
+            .wrap()
+            .query_balance(suite.distribution.addr(), ATOM)
+            .unwrap(),
+        coin(15, ATOM),
     );
 
+    let err = bob_proxy
+        .buy_vote_tokens(&mut suite.app, &bob, Some(Uint128::new(4)))
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast().unwrap(),
+        DistributionContractError::InsufficientRewards { available } if available == coin(15, ATOM)
+    ));
+
+    // the cost of this many vote tokens does not even fit in a Uint128
+    let err = bob_proxy
+        .buy_vote_tokens(&mut suite.app, &bob, Some(Uint128::MAX))
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast().unwrap(),
+        DistributionContractError::InsufficientRewards { available } if available == coin(15, ATOM)
+    ));
+
+    let bought = bob_proxy
+        .buy_vote_tokens(&mut suite.app, &bob, Some(Uint128::new(2)))
+        .unwrap()
+        .unwrap();
+
     assert_eq!(
-        member3_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp {
-            funds: Some(coin(20, ATOM))
+        bought,
+        BuyVoteTokensResp {
+            vote_tokens: suite.vote_coin(2),
+            change: coin(5, ATOM),
         }
     );
 
-    member1_proxy.withdraw(&mut app, &member1).unwrap();
+    bob_proxy
+        .buy_vote_tokens(&mut suite.app, &bob, None)
+        .unwrap();
 
+    assert_eq!(suite.vote_balance(bob_proxy.addr()), 5);
     assert_eq!(
-        app.wrap().query_balance(&member1, ATOM).unwrap(),
-        coin(50, ATOM),
+        bob_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp { funds: vec![] }
     );
-
     assert_eq!(
-        member1_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp { funds: None }
+        suite
+            .app
+            .wrap()
+            .query_balance(suite.distribution.addr(), ATOM)
+            .unwrap(),
+        coin(0, ATOM),
     );
-
     assert_eq!(
-        app.wrap()
-            .query_balance(distribution_contract.addr(), ATOM)
-            .unwrap(),
-        coin(50, ATOM),
+        suite.app.wrap().query_balance(&suite.admin, ATOM).unwrap(),
+        coin(15, ATOM),
     );
-
-    member2_proxy.buy_vote_tokens(&mut app, &member2).unwrap();
-
     assert_eq!(
-        app.wrap().query_balance(&member2, VOTE_DENOM).unwrap(),
-        coin(9, VOTE_DENOM),
+        suite
+            .distribution
+            .total_vote_tokens_in_circulation(&suite.app),
+        suite.vote_coin(13)
     );
+}
+
+#[test]
+fn member_proposal_passes_after_second_voter() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let charlie = Addr::unchecked("charlie");
+    let dave = Addr::unchecked("dave");
+
+    // 30 vote tokens in circulation, 10 for each member
+    let mut suite = SuiteBuilder::new()
+        .with_funds("dave", &coins(30, ATOM))
+        .build();
+
+    let dave_proposal = suite.propose_member(&alice, 5, &dave);
     assert_eq!(
-        member2_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp { funds: None }
+        dave_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Open
     );
 
+    // 10 out of 30 is not enough
+    suite
+        .proxy(&bob)
+        .vote(
+            &mut suite.app,
+            &bob,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            5,
+        )
+        .unwrap();
     assert_eq!(
-        app.wrap()
-            .query_balance(distribution_contract.addr(), ATOM)
-            .unwrap(),
-        coin(50, ATOM),
+        dave_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Open
     );
 
+    // 16 out of 30 is more than half
+    suite
+        .proxy(&charlie)
+        .vote(
+            &mut suite.app,
+            &charlie,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            6,
+        )
+        .unwrap();
     assert_eq!(
-        distribution_contract.total_vote_tokens_in_circulation(&app),
-        coin(25, VOTE_DENOM)
+        dave_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
     );
 
+    let dave_proxy_data = dave_proposal
+        .join(&mut suite.app, &dave, &coins(30, ATOM))
+        .unwrap()
+        .unwrap();
+
+    // initial votes are minted into the new member proxy
     assert_eq!(
-        app.wrap()
-            .query_balance(distribution_contract.addr(), VOTE_DENOM)
-            .unwrap(),
-        coin(76, VOTE_DENOM),
+        suite.vote_balance(&Addr::unchecked(dave_proxy_data.proxy_addr)),
+        10
     );
 }
 
 #[test]
-fn member_vote_flow_with_rewards_and_vote_tokens_buy() {
-    let admin = Addr::unchecked("admin");
+fn generic_proposal_executes_messages_when_passed() {
     let alice = Addr::unchecked("alice");
     let bob = Addr::unchecked("bob");
-    let members = [alice.as_str(), bob.as_str()];
     let charlie = Addr::unchecked("charlie");
-
-    let mut app = App::new(|router, _api, storage| {
-        router
-            .bank
-            .init_balance(storage, &admin, coins(100, VOTE_DENOM))
-            .unwrap();
-
-        router
-            .bank
-            .init_balance(storage, &charlie, coins(30, ATOM))
-            .unwrap();
-    });
-
-    let proxy_id = ProxyId::store_code(&mut app);
-    let proposal_id = ProposalId::store_code(&mut app);
-    let distribution_id = DistributionId::store_code(&mut app);
-    let membership_id = MembershipId::store_code(&mut app);
-
-    let (membership, data) = membership_id
-        .instantiate(
-            &mut app,
-            &admin,
-            Decimal::percent(10),
-            coin(5, ATOM),
-            coin(30, ATOM),
-            proxy_id,
-            proposal_id,
-            distribution_id,
-            &members,
-            "Membership",
-            &coins(100, VOTE_DENOM),
+    let grantee = Addr::unchecked("grantee");
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds("admin", &coins(50, ATOM))
+        .build();
+
+    suite
+        .app
+        .send_tokens(
+            suite.admin.clone(),
+            suite.membership.addr().clone(),
+            &coins(50, ATOM),
         )
         .unwrap();
 
-    let membership_config = membership.load_config(&app);
-    let distribution_contract =
-        DistributionContract::from_addr(membership_config.distribution_contract);
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+    let charlie_proxy = suite.proxy(&charlie);
 
-    let proxies: HashMap<_, _> = data
-        .members
-        .into_iter()
-        .map(|member| {
-            (
-                member.owner_addr,
-                ProxyContract::from_addr(Addr::unchecked(member.proxy_addr)),
-            )
-        })
-        .collect();
+    let grant = BankMsg::Send {
+        to_address: grantee.to_string(),
+        amount: coins(20, ATOM),
+    };
 
-    assert_eq!(proxies.len(), 2);
-    let alice_proxy = proxies.get(members[0]).unwrap();
-    let bob_proxy = proxies.get(members[1]).unwrap();
+    let proposal_data = alice_proxy
+        .propose_generic(
+            &mut suite.app,
+            &alice,
+            8,
+            "Grant",
+            "Fund the grantee",
+            vec![grant.into()],
+        )
+        .unwrap()
+        .unwrap();
 
-    assert!(
-        membership
-            .is_member(&app, alice_proxy.addr().as_str())
-            .unwrap()
-            .ok
+    let grant_proposal = ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
+
+    bob_proxy
+        .vote(
+            &mut suite.app,
+            &bob,
+            grant_proposal.addr(),
+            VoteOption::Yes,
+            6,
+        )
+        .unwrap();
+    assert_eq!(
+        grant_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Open
     );
-    assert!(
-        membership
-            .is_member(&app, bob_proxy.addr().as_str())
-            .unwrap()
-            .ok
+    assert_eq!(
+        suite.app.wrap().query_balance(&grantee, ATOM).unwrap(),
+        coin(0, ATOM)
     );
 
     assert_eq!(
-        app.wrap().query_balance(&alice, VOTE_DENOM).unwrap(),
-        coin(5, VOTE_DENOM),
+        suite.distribution.supply(&suite.app).locked,
+        suite.vote_coin(14)
+    );
+    suite.assert_supply_invariant();
+
+    charlie_proxy
+        .vote(
+            &mut suite.app,
+            &charlie,
+            grant_proposal.addr(),
+            VoteOption::Yes,
+            1,
+        )
+        .unwrap();
+    assert_eq!(
+        grant_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
+    );
+    assert_eq!(
+        suite.app.wrap().query_balance(&grantee, ATOM).unwrap(),
+        coin(20, ATOM)
     );
-
     assert_eq!(
-        app.wrap().query_balance(&bob, VOTE_DENOM).unwrap(),
-        coin(5, VOTE_DENOM),
+        suite
+            .app
+            .wrap()
+            .query_balance(suite.membership.addr(), ATOM)
+            .unwrap(),
+        coin(30, ATOM)
     );
 
+    assert_eq!(suite.membership.executing_proposal(&suite.app), None);
+
+    // passed escrow is back in the reserve and out of circulation
+    let supply = suite.distribution.supply(&suite.app);
+    assert_eq!(supply.circulating, suite.vote_coin(15));
+    assert_eq!(supply.locked, suite.vote_coin(0));
+    suite.assert_supply_invariant();
+
+    // a grant membership cannot pay does not keep the proposal from passing
+    suite.next_block();
+    let overdraft = BankMsg::Send {
+        to_address: grantee.to_string(),
+        amount: coins(100, ATOM),
+    };
     let proposal_data = alice_proxy
-        .propose_member(&mut app, &alice, &coins(3, VOTE_DENOM), &charlie)
+        .propose_generic(
+            &mut suite.app,
+            &alice,
+            2,
+            "Overdraft",
+            "Fund the grantee beyond the treasury",
+            vec![overdraft.into()],
+        )
         .unwrap()
         .unwrap();
-
-    assert_eq!(proposal_data.owner_addr, charlie.to_string());
-
-    let charlie_proposal =
+    let overdraft_proposal =
         ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
 
+    let resp = charlie_proxy
+        .vote(
+            &mut suite.app,
+            &charlie,
+            overdraft_proposal.addr(),
+            VoteOption::Yes,
+            9,
+        )
+        .unwrap();
     assert_eq!(
-        app.wrap()
-            .query_balance(charlie_proposal.addr(), VOTE_DENOM)
-            .unwrap(),
-        coin(3, VOTE_DENOM),
+        overdraft_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
     );
-
-    charlie_proposal
-        .vote(&mut app, &bob, &coins(3, VOTE_DENOM))
-        .unwrap();
-
+    assert!(resp.events.iter().any(|event| {
+        event.ty == "wasm-generic_proposal_msg_failed"
+            && event.attributes.iter().any(|attr| {
+                attr.key == "proposal" && attr.value == overdraft_proposal.addr().as_str()
+            })
+    }));
+    assert_eq!(suite.membership.executing_proposal(&suite.app), None);
     assert_eq!(
-        app.wrap()
-            .query_balance(charlie_proposal.addr(), VOTE_DENOM)
+        suite.app.wrap().query_balance(&grantee, ATOM).unwrap(),
+        coin(20, ATOM)
+    );
+    assert_eq!(
+        suite
+            .app
+            .wrap()
+            .query_balance(suite.membership.addr(), ATOM)
             .unwrap(),
-        coin(6, VOTE_DENOM),
+        coin(30, ATOM)
     );
 
-    let charlie_proxy_data = charlie_proposal
-        .join(&mut app, &charlie, &coins(30, ATOM))
-        .unwrap()
+    let supply = suite.distribution.supply(&suite.app);
+    assert_eq!(supply.circulating, suite.vote_coin(4));
+    assert_eq!(supply.locked, suite.vote_coin(0));
+    suite.assert_supply_invariant();
+}
+
+#[test]
+fn expired_member_proposal_refunds_voters_on_close() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let charlie = Addr::unchecked("charlie");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds("dave", &coins(30, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+    let charlie_proxy = suite.proxy(&charlie);
+
+    let dave_proposal = suite.propose_member(&alice, 5, &dave);
+    bob_proxy
+        .vote(
+            &mut suite.app,
+            &bob,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            3,
+        )
         .unwrap();
 
-    let charlie_proxy = ProxyContract::from_addr(Addr::unchecked(charlie_proxy_data.proxy_addr));
+    suite.advance_blocks(100);
+    dave_proposal.close(&mut suite.app, &dave).unwrap();
 
+    assert_eq!(
+        dave_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Closed
+    );
+    assert_eq!(suite.vote_balance(dave_proposal.addr()), 0);
+    assert_eq!(suite.vote_balance(alice_proxy.addr()), 10);
+    assert_eq!(suite.vote_balance(bob_proxy.addr()), 10);
     assert!(
-        membership
-            .is_member(&app, charlie_proxy.addr().as_str())
+        !suite
+            .membership
+            .is_proposed_member(&suite.app, dave.as_str())
             .unwrap()
             .ok
     );
 
+    // refunds are still in circulation, just no longer locked
     assert_eq!(
-        app.wrap().query_balance(&charlie, VOTE_DENOM).unwrap(),
-        coin(6, VOTE_DENOM),
+        suite.distribution.supply(&suite.app).locked,
+        suite.vote_coin(0)
     );
+    suite.assert_supply_invariant();
 
-    assert_eq!(
-        alice_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp {
-            funds: Some(coin(15, ATOM))
-        }
+    // candidate can be proposed again, a passed proposal the candidate does not join in time
+    // can be closed as well
+    let dave_proposal = suite.propose_and_pass(&alice, 5, &dave, &[(&charlie, 10)]);
+    assert!(
+        suite
+            .membership
+            .is_proposed_member(&suite.app, dave.as_str())
+            .unwrap()
+            .ok
     );
 
+    suite.advance_blocks(100);
+    dave_proposal.close(&mut suite.app, &dave).unwrap();
+
     assert_eq!(
-        bob_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp {
-            funds: Some(coin(15, ATOM))
-        }
+        dave_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Closed
+    );
+    for proxy in [&alice_proxy, &charlie_proxy] {
+        assert_eq!(suite.vote_balance(proxy.addr()), 10);
+    }
+    assert!(
+        !suite
+            .membership
+            .is_proposed_member(&suite.app, dave.as_str())
+            .unwrap()
+            .ok
     );
-
-    alice_proxy.withdraw(&mut app, &alice).unwrap();
-
     assert_eq!(
-        app.wrap().query_balance(&alice, ATOM).unwrap(),
-        coin(15, ATOM),
+        suite.distribution.supply(&suite.app).locked,
+        suite.vote_coin(0)
     );
+    suite.assert_supply_invariant();
+
+    // yes votes of the closed proposal are no longer shares of anything once settled
+    let shares = |app: &App, proxy: &ProxyContract| {
+        REWARD_SHARES
+            .query(
+                &app.wrap(),
+                suite.distribution.addr().clone(),
+                (proxy.addr(), dave_proposal.addr()),
+            )
+            .unwrap()
+    };
+    assert_eq!(shares(&suite.app, &charlie_proxy), Some(Uint128::new(10)));
+    charlie_proxy
+        .withdraw(&mut suite.app, &charlie, None)
+        .unwrap();
+    assert_eq!(shares(&suite.app, &charlie_proxy), None);
+}
+
+#[test]
+fn no_and_abstain_votes_count_towards_quorum_only_yes_voters_rewarded() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let charlie = Addr::unchecked("charlie");
+    let eve = Addr::unchecked("eve");
+    let dave = Addr::unchecked("dave");
+
+    // 40 vote tokens in circulation, 10 for each member
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob", "charlie", "eve"])
+        .with_pool_part(40)
+        .with_funds("dave", &coins(30, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+    let charlie_proxy = suite.proxy(&charlie);
+    let eve_proxy = suite.proxy(&eve);
+
+    let dave_proposal = suite.propose_member(&alice, 5, &dave);
+
+    bob_proxy
+        .vote(
+            &mut suite.app,
+            &bob,
+            dave_proposal.addr(),
+            VoteOption::No,
+            9,
+        )
+        .unwrap();
 
+    // quorum is reached, but yes votes are not above threshold
+    charlie_proxy
+        .vote(
+            &mut suite.app,
+            &charlie,
+            dave_proposal.addr(),
+            VoteOption::Abstain,
+            6,
+        )
+        .unwrap();
     assert_eq!(
-        alice_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp { funds: None }
+        dave_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Open
     );
 
+    eve_proxy
+        .vote(
+            &mut suite.app,
+            &eve,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            10,
+        )
+        .unwrap();
     assert_eq!(
-        app.wrap()
-            .query_balance(distribution_contract.addr(), ATOM)
-            .unwrap(),
-        coin(15, ATOM),
+        dave_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
     );
 
-    bob_proxy.buy_vote_tokens(&mut app, &bob).unwrap();
+    let tally = dave_proposal.tally(&suite.app).unwrap();
+    assert_eq!(tally.yes.u128(), 15);
+    assert_eq!(tally.no.u128(), 9);
+    assert_eq!(tally.abstain.u128(), 6);
 
+    dave_proposal
+        .join(&mut suite.app, &dave, &coins(30, ATOM))
+        .unwrap()
+        .unwrap();
+
+    for (proxy, funds) in [
+        (&alice_proxy, coins(10, ATOM)),
+        (&eve_proxy, coins(20, ATOM)),
+        (&bob_proxy, vec![]),
+        (&charlie_proxy, vec![]),
+    ] {
+        assert_eq!(
+            proxy.withdrawable(&suite.app).unwrap(),
+            WithdrawableResp { funds }
+        );
+    }
+}
+
+#[test]
+fn migrate_children_moves_children_to_new_code_in_batches() {
+    let member1 = Addr::unchecked("member1");
+    let candidate = Addr::unchecked("candidate");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["member1", "member2", "member3"])
+        .with_pool_part(19)
+        .build();
+
+    let mut proxies: Vec<_> = ["member1", "member2", "member3"]
+        .into_iter()
+        .map(|member| suite.proxy(&Addr::unchecked(member)).addr().clone())
+        .collect();
+    proxies.sort();
+
+    let proposal = suite.propose_member(&member1, 5, &candidate);
+
+    let new_proxy_id = u64::from(ProxyId::store_code(&mut suite.app));
+    let new_proposal_id = u64::from(ProposalId::store_code(&mut suite.app));
+    let new_distribution_id = u64::from(DistributionId::store_code(&mut suite.app));
+
+    let err = suite
+        .membership
+        .migrate_children(
+            &mut suite.app,
+            &member1,
+            new_proxy_id,
+            ChildKind::Proxy,
+            None,
+            None,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::Unauthorized
+    ));
+
+    suite
+        .membership
+        .migrate_children(
+            &mut suite.app,
+            &suite.admin,
+            new_proxy_id,
+            ChildKind::Proxy,
+            None,
+            Some(2),
+        )
+        .unwrap();
+
+    let code_id =
+        |app: &App, addr: &Addr| app.wrap().query_wasm_contract_info(addr).unwrap().code_id;
+
+    assert_eq!(code_id(&suite.app, &proxies[0]), new_proxy_id);
+    assert_eq!(code_id(&suite.app, &proxies[1]), new_proxy_id);
+    assert_ne!(code_id(&suite.app, &proxies[2]), new_proxy_id);
+
+    suite
+        .membership
+        .migrate_children(
+            &mut suite.app,
+            &suite.admin,
+            new_proxy_id,
+            ChildKind::Proxy,
+            Some(proxies[1].to_string()),
+            Some(2),
+        )
+        .unwrap();
+
+    assert_eq!(code_id(&suite.app, &proxies[2]), new_proxy_id);
     assert_eq!(
-        app.wrap().query_balance(&bob, VOTE_DENOM).unwrap(),
-        coin(5, VOTE_DENOM),
-    );
-    assert_eq!(
-        bob_proxy.withdrawable(&app).unwrap(),
-        WithdrawableResp { funds: None }
+        suite.membership.load_config(&suite.app).proxy_code_id,
+        new_proxy_id
     );
 
+    suite
+        .membership
+        .migrate_children(
+            &mut suite.app,
+            &suite.admin,
+            new_proposal_id,
+            ChildKind::Proposal,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(code_id(&suite.app, proposal.addr()), new_proposal_id);
+
+    suite
+        .membership
+        .migrate_children(
+            &mut suite.app,
+            &suite.admin,
+            new_distribution_id,
+            ChildKind::Distribution,
+            None,
+            None,
+        )
+        .unwrap();
     assert_eq!(
-        app.wrap()
-            .query_balance(distribution_contract.addr(), ATOM)
-            .unwrap(),
-        coin(15, ATOM),
+        code_id(&suite.app, suite.distribution.addr()),
+        new_distribution_id
     );
+}
+
+#[test]
+fn member_and_candidate_listing_queries() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds("dave", &coins(30, ATOM))
+        .build();
 
+    let alice_proxy = suite.proxy(&alice);
+
+    assert_eq!(suite.membership.member_count(&suite.app).unwrap().count, 3);
+
+    let first_page = suite
+        .membership
+        .list_members(&suite.app, None, Some(2))
+        .unwrap();
+    assert_eq!(first_page.members.len(), 2);
+
+    let last = Addr::unchecked(&first_page.members[1].proxy);
+    let second_page = suite
+        .membership
+        .list_members(&suite.app, Some(&last), Some(2))
+        .unwrap();
+    assert_eq!(second_page.members.len(), 1);
+
+    let mut listed: Vec<_> = first_page
+        .members
+        .into_iter()
+        .chain(second_page.members)
+        .map(|member| {
+            assert_eq!(
+                suite.proxy(&Addr::unchecked(&member.owner)).addr().as_str(),
+                member.proxy
+            );
+            assert_eq!(member.vote_tokens, suite.vote_coin(10));
+            member.owner
+        })
+        .collect();
+    listed.sort();
+    assert_eq!(listed, ["alice", "bob", "charlie"]);
+
+    let dave_proposal = suite.propose_member(&alice, 5, &dave);
+
+    let candidates = suite
+        .membership
+        .list_candidates(&suite.app, None, None)
+        .unwrap();
+    assert_eq!(
+        candidates.candidates,
+        vec![CandidateResp {
+            candidate: dave.to_string(),
+            proposal: dave_proposal.addr().to_string(),
+        }]
+    );
+    assert!(suite
+        .membership
+        .list_candidates(&suite.app, Some(&dave), None)
+        .unwrap()
+        .candidates
+        .is_empty());
+
+    suite
+        .proxy(&bob)
+        .vote(
+            &mut suite.app,
+            &bob,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            10,
+        )
+        .unwrap();
+    dave_proposal
+        .join(&mut suite.app, &dave, &coins(30, ATOM))
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(suite.membership.member_count(&suite.app).unwrap().count, 4);
+
+    let alice_profile = suite.membership.member_profile(&suite.app, &alice).unwrap();
+    assert_eq!(
+        alice_profile,
+        MemberProfileResp {
+            owner: alice.to_string(),
+            proxy: alice_proxy.addr().to_string(),
+            joined_height: suite.genesis.height,
+            joined_time: suite.genesis.time,
+            proposal: None,
+            initial_votes: suite.vote_coin(10),
+            withdrawable: vec![coin(10, ATOM)],
+        }
+    );
+
+    let dave_profile = suite.membership.member_profile(&suite.app, &dave).unwrap();
+    assert_eq!(
+        dave_profile.proposal,
+        Some(dave_proposal.addr().to_string())
+    );
+    assert_eq!(dave_profile.initial_votes, suite.vote_coin(10));
+    assert_eq!(dave_profile.withdrawable, vec![]);
+
+    let dave_proxy = Addr::unchecked(dave_profile.proxy);
+    assert_eq!(
+        suite
+            .membership
+            .member_by_proxy(&suite.app, &dave_proxy)
+            .unwrap(),
+        OwnerProxyResp {
+            owner: dave.to_string(),
+            proxy: dave_proxy.to_string(),
+        }
+    );
+    suite
+        .membership
+        .member_by_proxy(&suite.app, &dave)
+        .unwrap_err();
+    assert!(suite
+        .membership
+        .list_candidates(&suite.app, None, None)
+        .unwrap()
+        .candidates
+        .is_empty());
+}
+
+/// Contract posing as a member proposal of `candidate`, it asks membership to admit the
+/// candidate when executed
+fn rogue_proposal(app: &mut App, membership: &Addr, candidate: &Addr) -> Addr {
+    const MEMBERSHIP: Item<Addr> = Item::new("membership");
+
+    let instantiate =
+        |deps: DepsMut, _: Env, _: MessageInfo, (membership, candidate): (Addr, Addr)| {
+            MEMBERSHIP.save(deps.storage, &membership)?;
+            proposal::state::OWNER.save(deps.storage, &candidate)?;
+            StdResult::Ok(Response::new())
+        };
+    let execute = |deps: DepsMut, _: Env, info: MessageInfo, _: Empty| {
+        let msg = WasmMsg::Execute {
+            contract_addr: MEMBERSHIP.load(deps.storage)?.into_string(),
+            msg: to_json_binary(&MembershipExecMsg::NewMember {})?,
+            funds: info.funds,
+        };
+        StdResult::Ok(Response::new().add_message(msg))
+    };
+    let query = |_: Deps, _: Env, _: Empty| -> StdResult<Binary> {
+        Err(StdError::generic_err("not supported by the mock"))
+    };
+
+    let code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        execute,
+        instantiate,
+        query,
+    )));
+    app.instantiate_contract(
+        code_id,
+        candidate.clone(),
+        &(membership, candidate),
+        &[],
+        "Rogue proposal",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn candidate_joins_only_through_its_own_proposal() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds("dave", &coins(30, ATOM))
+        .build();
+
+    let dave_proposal = suite.propose_and_pass(&alice, 5, &dave, &[(&bob, 10)]);
+
+    // a second proposal of dave which membership did not instantiate cannot admit him
+    let membership = suite.membership.addr().clone();
+    let rogue = rogue_proposal(&mut suite.app, &membership, &dave);
+    let err = suite
+        .app
+        .execute_contract(dave.clone(), rogue, &Empty {}, &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::Unauthorized
+    ));
+    assert_eq!(suite.membership.member_count(&suite.app).unwrap().count, 3);
+
+    dave_proposal
+        .join(&mut suite.app, &dave, &coins(30, ATOM))
+        .unwrap()
+        .unwrap();
+    assert_eq!(suite.membership.member_count(&suite.app).unwrap().count, 4);
+    suite.assert_supply_invariant();
+}
+
+#[test]
+fn config_updated_by_generic_proposal_or_admin() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+
+    let mut suite = SuiteBuilder::new().build();
+    let admin = suite.admin.clone();
+
+    let err = suite
+        .membership
+        .update_config(
+            &mut suite.app,
+            &alice,
+            ConfigUpdate {
+                quorum: Some(Decimal::percent(60)),
+                ..ConfigUpdate::default()
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::Unauthorized
+    ));
+
+    let err = suite
+        .membership
+        .update_config(
+            &mut suite.app,
+            &admin,
+            ConfigUpdate {
+                quorum: Some(Decimal::percent(0)),
+                ..ConfigUpdate::default()
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::InitialisationInvalidQuorum
+    ));
+
+    let vote_fee = suite.vote_coin(50);
+    let err = suite
+        .membership
+        .update_config(
+            &mut suite.app,
+            &admin,
+            ConfigUpdate {
+                joining_fee: Some(vec![vote_fee]),
+                ..ConfigUpdate::default()
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::JoiningFeeDenomInvalid { .. }
+    ));
+
+    suite
+        .membership
+        .update_config(
+            &mut suite.app,
+            &admin,
+            ConfigUpdate {
+                vote_token_price: Some(coin(10, ATOM)),
+                ..ConfigUpdate::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        suite.distribution.load_config(&suite.app).vote_token_price,
+        coin(10, ATOM)
+    );
+
+    let update = WasmMsg::Execute {
+        contract_addr: suite.membership.addr().to_string(),
+        msg: to_json_binary(&MembershipExecMsg::UpdateConfig {
+            config: ConfigUpdate {
+                joining_fee: Some(vec![coin(50, ATOM)]),
+                threshold: Some(Decimal::percent(60)),
+                ..ConfigUpdate::default()
+            },
+        })
+        .unwrap(),
+        funds: vec![],
+    };
+
+    let proposal_data = suite
+        .proxy(&alice)
+        .propose_generic(
+            &mut suite.app,
+            &alice,
+            8,
+            "Raise joining fee",
+            "Raise the joining fee and the pass threshold",
+            vec![update.into()],
+        )
+        .unwrap()
+        .unwrap();
+
+    let update_proposal = ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
+    suite
+        .proxy(&bob)
+        .vote(
+            &mut suite.app,
+            &bob,
+            update_proposal.addr(),
+            VoteOption::Yes,
+            7,
+        )
+        .unwrap();
+    assert_eq!(
+        update_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
+    );
+
+    let config = suite.membership.load_config(&suite.app);
+    assert_eq!(config.joining_fee, coins(50, ATOM));
+    assert_eq!(config.threshold, Decimal::percent(60));
+    assert_eq!(config.quorum, Decimal::percent(50));
+}
+
+#[test]
+fn members_leave_or_are_removed_by_proposal() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let charlie = Addr::unchecked("charlie");
+    let eve = Addr::unchecked("eve");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob", "charlie", "eve"])
+        .with_pool_part(40)
+        .with_funds("dave", &coins(30, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let charlie_proxy = suite.proxy(&charlie);
+    let eve_proxy = suite.proxy(&eve);
+
+    // alice and bob earn rewards by admitting dave
+    let dave_proxy = suite.admit(&alice, 10, &dave, &[(&bob, 10)], &coins(30, ATOM));
+
+    assert_eq!(suite.membership.member_count(&suite.app).unwrap().count, 5);
+    assert_eq!(
+        suite
+            .distribution
+            .total_vote_tokens_in_circulation(&suite.app),
+        suite.vote_coin(30)
+    );
+    suite.assert_supply_invariant();
+
+    // charlie leaves, his proxy hands back the vote tokens it holds
+    let pool = suite.vote_balance(suite.distribution.addr());
+    charlie_proxy.leave(&mut suite.app, &charlie, &[]).unwrap();
+
+    assert!(
+        !suite
+            .membership
+            .is_member(&suite.app, charlie_proxy.addr().as_str())
+            .unwrap()
+            .ok
+    );
+    assert_eq!(
+        suite
+            .distribution
+            .total_vote_tokens_in_circulation(&suite.app),
+        suite.vote_coin(20)
+    );
+    suite.assert_supply_invariant();
+    // returned to the reserve, or burned by a token factory distribution
+    let returned = if TOKEN_FACTORY { 0 } else { 10 };
+    assert_eq!(
+        suite.vote_balance(suite.distribution.addr()),
+        pool + returned
+    );
+
+    // alice leaves and gets her unclaimed rewards
+    alice_proxy.leave(&mut suite.app, &alice, &[]).unwrap();
+
+    assert!(
+        !suite
+            .membership
+            .is_member(&suite.app, alice_proxy.addr().as_str())
+            .unwrap()
+            .ok
+    );
+    assert_eq!(
+        suite.app.wrap().query_balance(&alice, ATOM).unwrap(),
+        coin(15, ATOM)
+    );
+
+    let err = alice_proxy.leave(&mut suite.app, &alice, &[]).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        DistributionContractError::Unauthorized
+    ));
+
+    let err = eve_proxy
+        .propose_removal(&mut suite.app, &eve, 5, &alice)
+        .unwrap_err();
+    assert!(matches!(err.downcast().unwrap(), ContractError::NotAMember));
+
+    suite.next_block();
+
+    // bob is removed by eve and dave
+    let removal_data = eve_proxy
+        .propose_removal(&mut suite.app, &eve, 10, &bob)
+        .unwrap()
+        .unwrap();
+    assert_eq!(removal_data.owner_addr, bob.to_string());
+
+    let err = dave_proxy
+        .propose_removal(&mut suite.app, &dave, 1, &bob)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::ExistingProposalInProgress
+    ));
+
+    let removal_proposal = ProposalContract::from_addr(Addr::unchecked(removal_data.proposal_addr));
+    dave_proxy
+        .vote(
+            &mut suite.app,
+            &dave,
+            removal_proposal.addr(),
+            VoteOption::Yes,
+            9,
+        )
+        .unwrap();
+    assert_eq!(
+        removal_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
+    );
+
+    assert_eq!(suite.membership.member_count(&suite.app).unwrap().count, 2);
+    assert!(suite.membership.member_profile(&suite.app, &bob).is_err());
+    assert_eq!(
+        suite.app.wrap().query_balance(&bob, ATOM).unwrap(),
+        coin(15, ATOM)
+    );
+
+    // removal escrow went back to the reserve, only dave's last vote token is left
+    let supply = suite.distribution.supply(&suite.app);
+    assert_eq!(supply.circulating, suite.vote_coin(1));
+    assert_eq!(supply.locked, suite.vote_coin(0));
+    suite.assert_supply_invariant();
+}
+
+#[test]
+fn escrow_of_members_who_left_is_burned_when_the_proposal_closes() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new().build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+
+    let dave_proposal = suite.propose_member(&alice, 5, &dave);
+    bob_proxy
+        .vote(
+            &mut suite.app,
+            &bob,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            3,
+        )
+        .unwrap();
+
+    // bob leaves with 3 vote tokens still escrowed, the other 7 are burned right away
+    bob_proxy.leave(&mut suite.app, &bob, &[]).unwrap();
+    let supply = suite.distribution.supply(&suite.app);
+    assert_eq!(supply.circulating, suite.vote_coin(23));
+    assert_eq!(supply.locked, suite.vote_coin(8));
+    suite.assert_supply_invariant();
+
+    suite.advance_blocks(100);
+    dave_proposal.close(&mut suite.app, &dave).unwrap();
+
+    // only alice gets her escrow back, bob's is burned, his shares were dropped as he left
+    assert_eq!(suite.vote_balance(alice_proxy.addr()), 10);
+    assert_eq!(suite.vote_balance(bob_proxy.addr()), 0);
+    let supply = suite.distribution.supply(&suite.app);
+    assert_eq!(supply.circulating, suite.vote_coin(20));
+    assert_eq!(supply.locked, suite.vote_coin(0));
+    suite.assert_supply_invariant();
+
+    let shares = REWARD_SHARES
+        .query(
+            &suite.app.wrap(),
+            suite.distribution.addr().clone(),
+            (bob_proxy.addr(), dave_proposal.addr()),
+        )
+        .unwrap();
+    assert_eq!(shares, None);
+}
+
+#[test]
+fn yes_voters_who_left_share_no_joining_fee() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds("dave", &coins(30, ATOM))
+        .build();
+
+    let bob_proxy = suite.proxy(&bob);
+    let dave_proposal = suite.propose_and_pass(&alice, 5, &dave, &[(&bob, 10)]);
+
+    // bob votes yes and leaves before dave joins, his shares go with him
+    bob_proxy.leave(&mut suite.app, &bob, &[]).unwrap();
+    let shares = REWARD_SHARES
+        .query(
+            &suite.app.wrap(),
+            suite.distribution.addr().clone(),
+            (bob_proxy.addr(), dave_proposal.addr()),
+        )
+        .unwrap();
+    assert_eq!(shares, None);
+
+    dave_proposal
+        .join(&mut suite.app, &dave, &coins(30, ATOM))
+        .unwrap()
+        .unwrap();
+    suite.assert_supply_invariant();
+
+    // alice is the only yes voter left, the whole joining fee is hers
+    let alice_profile = suite.membership.member_profile(&suite.app, &alice).unwrap();
+    assert_eq!(alice_profile.withdrawable, vec![coin(30, ATOM)]);
+    suite
+        .proxy(&alice)
+        .withdraw(&mut suite.app, &alice, None)
+        .unwrap();
+    assert_eq!(
+        suite
+            .app
+            .wrap()
+            .query_balance(suite.distribution.addr(), ATOM)
+            .unwrap(),
+        coin(0, ATOM)
+    );
+}
+
+fn cw20_send(
+    app: &mut App,
+    token: &Addr,
+    sender: &Addr,
+    contract: &Addr,
+    amount: u128,
+    msg: &impl Serialize,
+) -> AnyResult<AppResponse> {
+    let msg = Cw20ExecuteMsg::Send {
+        contract: contract.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_json_binary(msg)?,
+    };
+    app.execute_contract(sender.clone(), token.clone(), &msg, &[])
+}
+
+#[test]
+fn cw20_vote_token_minted_by_distribution() {
+    let member1 = Addr::unchecked("member1");
+    let member2 = Addr::unchecked("member2");
+    let candidate = Addr::unchecked("candidate");
+    let members = ["member1", "member2", "member3"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&members)
+        .with_pool_part(0)
+        .with_cw20_vote_token()
+        .with_funds("candidate", &coins(100, ATOM))
+        .build();
+
+    let VoteAsset::Cw20(_) = suite.vote_token else {
+        panic!("expected a cw20 vote token");
+    };
+
+    // initial votes are minted into the proxies
+    for member in members {
+        let proxy = suite.proxy(&Addr::unchecked(member));
+        assert_eq!(suite.vote_balance(proxy.addr()), 10);
+    }
+    assert_eq!(suite.vote_balance(&member1), 0);
+
+    suite
+        .proxy(&member1)
+        .propose_member(&mut suite.app, &member1, 5, &candidate)
+        .unwrap();
+
+    let candidates = suite
+        .membership
+        .list_candidates(&suite.app, None, None)
+        .unwrap();
+    let candidate_proposal =
+        ProposalContract::from_addr(Addr::unchecked(&candidates.candidates[0].proposal));
+
+    assert_eq!(suite.vote_balance(candidate_proposal.addr()), 5);
+    assert_eq!(
+        suite.distribution.supply(&suite.app).locked,
+        suite.vote_coin(5)
+    );
+
+    // the proxy sends its cw20 vote tokens along with the vote
+    suite
+        .proxy(&member2)
+        .vote(
+            &mut suite.app,
+            &member2,
+            candidate_proposal.addr(),
+            VoteOption::Yes,
+            10,
+        )
+        .unwrap();
+
+    assert_eq!(
+        candidate_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
+    );
+
+    candidate_proposal
+        .join(&mut suite.app, &candidate, &coins(30, ATOM))
+        .unwrap();
+
+    // escrow is burned, the new member gets initial votes minted
+    assert_eq!(suite.vote_balance(candidate_proposal.addr()), 0);
+    let candidate_proxy = suite.proxy(&candidate);
+    assert_eq!(suite.vote_balance(candidate_proxy.addr()), 10);
+    assert_eq!(suite.vote_balance(suite.distribution.addr()), 0);
+
+    let supply = suite.distribution.supply(&suite.app);
+    assert_eq!(supply.circulating, suite.vote_coin(25));
+    assert_eq!(supply.locked, suite.vote_coin(0));
+    suite.assert_supply_invariant();
+}
+
+#[test]
+fn cw20_vote_token_pool_funded_by_admin() {
+    let members = ["member1", "member2", "member3"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&members)
+        .with_cw20_vote_token_held_by_admin(110)
+        .build();
+
+    let VoteAsset::Cw20(token) = suite.vote_token.clone() else {
+        panic!("expected a cw20 vote token");
+    };
+
+    // nothing is minted until the pool is funded
+    let member1_proxy = suite.proxy(&Addr::unchecked("member1"));
+    assert_eq!(suite.vote_balance(member1_proxy.addr()), 0);
+
+    cw20_send(
+        &mut suite.app,
+        &token,
+        &suite.admin,
+        suite.membership.addr(),
+        100,
+        &MembershipExecMsg::FundPool {},
+    )
+    .unwrap();
+
+    for member in members {
+        let member = Addr::unchecked(member);
+        assert_eq!(suite.vote_balance(suite.proxy(&member).addr()), 10);
+        assert_eq!(
+            suite
+                .membership
+                .member_profile(&suite.app, &member)
+                .unwrap()
+                .initial_votes,
+            suite.vote_coin(10)
+        );
+    }
+    assert_eq!(suite.vote_balance(suite.distribution.addr()), 70);
+
+    let supply = suite.distribution.supply(&suite.app);
+    assert_eq!(supply.circulating, suite.vote_coin(30));
+    suite.assert_supply_invariant();
+
+    let err = cw20_send(
+        &mut suite.app,
+        &token,
+        &suite.admin,
+        suite.membership.addr(),
+        10,
+        &MembershipExecMsg::FundPool {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::PoolAlreadyFunded.to_string()
+    );
+}
+
+#[test]
+fn fees_paid_in_configured_reward_denom() {
+    // joining fee in another denom than the reward one
+    let err = SuiteBuilder::new()
+        .with_members(&["member1", "member2"])
+        .with_pool_part(0)
+        .with_vote_token_price(coin(5, "uosmo"))
+        .with_reward_denoms(&["uosmo"])
+        .try_build()
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::JoiningFeeDenomInvalid { denom } if denom == "uosmo"
+    ));
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["member1", "member2"])
+        .with_pool_part(0)
+        .with_vote_token_price(coin(5, "uosmo"))
+        .with_joining_fee(&coins(30, "uosmo"))
+        .with_reward_denoms(&["uosmo"])
+        .build();
+
+    assert_eq!(
+        suite.distribution.load_config(&suite.app).reward_denoms,
+        ["uosmo"]
+    );
+
+    let admin = suite.admin.clone();
+    let err = suite
+        .membership
+        .update_config(
+            &mut suite.app,
+            &admin,
+            ConfigUpdate {
+                vote_token_price: Some(coin(5, ATOM)),
+                ..ConfigUpdate::default()
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::VoteTokenPriceInvalid { denom } if denom == "uosmo"
+    ));
+}
+
+#[test]
+fn joining_fee_paid_in_any_of_several_denoms() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let charlie = Addr::unchecked("charlie");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob"])
+        .with_pool_part(10)
+        .with_joining_fee(&[coin(30, ATOM), coin(24, "uosmo")])
+        .with_initial_votes(6)
+        .with_reward_denoms(&[ATOM, "uosmo"])
+        .with_funds("charlie", &coins(24, "uosmo"))
+        .with_funds("dave", &coins(30, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+
+    let charlie_proxy = suite.admit(&alice, 3, &charlie, &[(&bob, 3)], &coins(24, "uosmo"));
+    suite.next_block();
+
+    suite.admit(
+        &alice,
+        2,
+        &dave,
+        &[(&bob, 2), (&charlie, 2)],
+        &coins(30, ATOM),
+    );
+
+    // each denom keeps its own ledger
+    assert_eq!(
+        alice_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp {
+            funds: vec![coin(10, ATOM), coin(12, "uosmo")]
+        }
+    );
+    assert_eq!(
+        charlie_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp {
+            funds: vec![coin(10, ATOM)]
+        }
+    );
+
+    bob_proxy
+        .withdraw(&mut suite.app, &bob, Some(coins(5, "uosmo")))
+        .unwrap();
+    assert_eq!(
+        bob_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp {
+            funds: vec![coin(10, ATOM), coin(7, "uosmo")]
+        }
+    );
+
+    let err = bob_proxy
+        .withdraw(
+            &mut suite.app,
+            &bob,
+            Some(vec![coin(5, ATOM), coin(8, "uosmo")]),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        DistributionContractError::InsufficientRewards { available } if available == coin(7, "uosmo")
+    ));
+
+    alice_proxy.withdraw(&mut suite.app, &alice, None).unwrap();
+    assert_eq!(
+        suite.app.wrap().query_all_balances(&alice).unwrap(),
+        vec![coin(10, ATOM), coin(12, "uosmo")]
+    );
+    assert_eq!(
+        alice_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp { funds: vec![] }
+    );
+}
+
+#[test]
+fn joining_fee_distributed_once_and_settled_by_each_voter() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+    let charlie = Addr::unchecked("charlie");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob", "carol"])
+        .with_pool_part(15)
+        .with_joining_fee(&coins(32, ATOM))
+        .with_initial_votes(6)
+        .with_funds("charlie", &coins(32, ATOM))
+        .with_funds("dave", &coins(32, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+    let carol_proxy = suite.proxy(&carol);
+
+    // 8 out of 15 passes
+    let charlie_proposal = suite.propose_and_pass(&alice, 3, &charlie, &[(&bob, 2), (&carol, 3)]);
+
+    // whatever the number of voters, the fee is distributed in one go
+    let resp = suite
+        .app
+        .execute_contract(
+            charlie.clone(),
+            charlie_proposal.addr().clone(),
+            &ProposalExecMsg::Join {},
+            &coins(32, ATOM),
+        )
+        .unwrap();
+    let distributions: Vec<_> = resp
+        .events
+        .iter()
+        .filter(|event| event.ty == "wasm-reward_distribution")
+        .collect();
+    assert_eq!(distributions.len(), 1);
+    assert!(distributions[0]
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "points_per_weight" && attr.value == "400"));
+
+    assert_eq!(
+        alice_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp {
+            funds: vec![coin(12, ATOM)]
+        }
+    );
+    assert_eq!(
+        bob_proxy.withdrawable(&suite.app).unwrap(),
+        WithdrawableResp {
+            funds: vec![coin(8, ATOM)]
+        }
+    );
+
+    let charlie_proxy = suite.proxy(&charlie);
+    suite.next_block();
+
+    // 5 out of 13 does not, yet
+    let dave_proposal = suite.propose_member(&alice, 2, &dave);
+    bob_proxy
+        .vote(
+            &mut suite.app,
+            &bob,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            3,
+        )
+        .unwrap();
+
+    bob_proxy.withdraw(&mut suite.app, &bob, None).unwrap();
+    assert_eq!(
+        suite.app.wrap().query_balance(&bob, ATOM).unwrap(),
+        coin(8, ATOM)
+    );
+
+    // shares in dave's proposal were kept while bob settled charlie's
+    charlie_proxy
+        .vote(
+            &mut suite.app,
+            &charlie,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            1,
+        )
+        .unwrap();
+    carol_proxy
+        .vote(
+            &mut suite.app,
+            &carol,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            2,
+        )
+        .unwrap();
+    dave_proposal
+        .join(&mut suite.app, &dave, &coins(32, ATOM))
+        .unwrap();
+
+    for (proxy, reward) in [
+        (&alice_proxy, 20),
+        (&bob_proxy, 12),
+        (&carol_proxy, 20),
+        (&charlie_proxy, 4),
+    ] {
+        assert_eq!(
+            proxy.withdrawable(&suite.app).unwrap(),
+            WithdrawableResp {
+                funds: vec![coin(reward, ATOM)]
+            }
+        );
+    }
+}
+
+#[test]
+fn proposals_tally_voting_power_at_their_start_height() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+    let charlie = Addr::unchecked("charlie");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob", "carol"])
+        .with_pool_part(15)
+        .with_vote_token_price(coin(4, ATOM))
+        .with_joining_fee(&coins(32, ATOM))
+        .with_initial_votes(6)
+        .with_funds("charlie", &coins(32, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+
+    let charlie_proposal = suite.propose_and_pass(&alice, 3, &charlie, &[(&bob, 2), (&carol, 3)]);
+
+    suite.next_block();
+    let start_height = suite.app.block_info().height;
+
+    let dave_proposal = suite.propose_member(&alice, 1, &dave);
+    assert_eq!(
+        dave_proposal.status(&suite.app).unwrap().start_height,
+        start_height
+    );
+
+    // charlie joins and alice buys a vote token in the block dave's proposal starts
+    charlie_proposal
+        .join(&mut suite.app, &charlie, &coins(32, ATOM))
+        .unwrap();
+    let charlie_proxy = suite.proxy(&charlie);
+    alice_proxy
+        .buy_vote_tokens(&mut suite.app, &alice, Some(Uint128::one()))
+        .unwrap();
+    assert_eq!(
+        suite
+            .distribution
+            .total_vote_tokens_in_circulation(&suite.app),
+        suite.vote_coin(14)
+    );
+
+    // neither counts towards the proposal
+    assert_eq!(
+        dave_proposal
+            .threshold(&suite.app)
+            .unwrap()
+            .total_vote_tokens_in_circulation,
+        suite.vote_coin(15)
+    );
+
+    let err = charlie_proxy
+        .vote(
+            &mut suite.app,
+            &charlie,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            1,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ProposalContractError::VoteRejectedInsufficientPower { power } if power.is_zero()
+    ));
+
+    // alice had 2 left at the start height, 1 of them is the proposal escrow
+    let err = alice_proxy
+        .vote(
+            &mut suite.app,
+            &alice,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            2,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ProposalContractError::VoteRejectedInsufficientPower { power } if power == Uint128::new(2)
+    ));
+    alice_proxy
+        .vote(
+            &mut suite.app,
+            &alice,
+            dave_proposal.addr(),
+            VoteOption::Yes,
+            1,
+        )
+        .unwrap();
+
+    // from the next block on, new proposals count both
+    suite.next_block();
+    let proposal_data = charlie_proxy
+        .propose_removal(&mut suite.app, &charlie, 6, &bob)
+        .unwrap()
+        .unwrap();
+    let removal_proposal =
+        ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
+    assert_eq!(
+        removal_proposal
+            .threshold(&suite.app)
+            .unwrap()
+            .total_vote_tokens_in_circulation,
+        suite.vote_coin(14)
+    );
+}
+
+#[test]
+fn voting_power_queries_for_dao_tooling() {
+    let alice = Addr::unchecked("alice");
+    let charlie = Addr::unchecked("charlie");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob", "carol"])
+        .with_pool_part(15)
+        .with_joining_fee(&coins(10, ATOM))
+        .with_initial_votes(6)
+        .build();
+    let proposed_at = suite.app.block_info().height;
+
+    suite.propose_member(&alice, 3, &charlie);
+
+    suite.next_block();
+    let now = suite.app.block_info().height;
+
+    // the escrow stops counting from the block after the proposal
+    assert_eq!(
+        suite
+            .membership
+            .voting_power_at_height(&suite.app, &alice, Some(proposed_at))
+            .unwrap(),
+        VotingPowerAtHeightResp {
+            power: Uint128::new(5),
+            height: proposed_at
+        }
+    );
+    assert_eq!(
+        suite
+            .membership
+            .voting_power_at_height(&suite.app, &alice, None)
+            .unwrap(),
+        VotingPowerAtHeightResp {
+            power: Uint128::new(2),
+            height: now
+        }
+    );
+    assert_eq!(
+        suite
+            .membership
+            .voting_power_at_height(&suite.app, &charlie, None)
+            .unwrap()
+            .power,
+        Uint128::zero()
+    );
+
+    // locked vote tokens are still in circulation
+    assert_eq!(
+        suite
+            .membership
+            .total_power_at_height(&suite.app, None)
+            .unwrap(),
+        TotalPowerAtHeightResp {
+            power: Uint128::new(15),
+            height: now
+        }
+    );
+    assert_eq!(
+        suite
+            .membership
+            .total_power_at_height(&suite.app, Some(1))
+            .unwrap()
+            .power,
+        Uint128::zero()
+    );
+
+    let info: InfoResp = suite
+        .app
+        .wrap()
+        .query_wasm_smart(suite.membership.addr(), &MembershipQueryMsg::Info {})
+        .unwrap();
+    assert_eq!(info.info.contract, "membership");
+
+    let dao: Addr = suite
+        .app
+        .wrap()
+        .query_wasm_smart(suite.membership.addr(), &MembershipQueryMsg::Dao {})
+        .unwrap();
+    assert_eq!(&dao, suite.membership.addr());
+}
+
+#[test]
+fn votes_retracted_while_the_proposal_is_open() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+    let charlie = Addr::unchecked("charlie");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob", "carol"])
+        .with_pool_part(15)
+        .with_joining_fee(&coins(32, ATOM))
+        .with_initial_votes(6)
+        .with_funds("charlie", &coins(32, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+    let carol_proxy = suite.proxy(&carol);
+
+    let proposal = suite.propose_member(&alice, 3, &charlie);
+
+    // 7 out of 15 does not pass, bob takes 2 back to his proxy
+    bob_proxy
+        .vote(&mut suite.app, &bob, proposal.addr(), VoteOption::Yes, 4)
+        .unwrap();
+    bob_proxy
+        .retract(&mut suite.app, &bob, proposal.addr(), 2)
+        .unwrap();
+
+    assert_eq!(proposal.tally(&suite.app).unwrap().yes, Uint128::new(5));
+    assert_eq!(suite.vote_balance(bob_proxy.addr()), 3);
+    assert_eq!(
+        suite
+            .membership
+            .voting_power_at_height(&suite.app, &bob, None)
+            .unwrap()
+            .power,
+        Uint128::new(3)
+    );
+    assert_eq!(
+        suite.distribution.supply(&suite.app).locked,
+        suite.vote_coin(5)
+    );
+
+    // a vote retracted in full frees the voter to pick another option
+    carol_proxy
+        .vote(&mut suite.app, &carol, proposal.addr(), VoteOption::No, 1)
+        .unwrap();
+    carol_proxy
+        .retract(&mut suite.app, &carol, proposal.addr(), 1)
+        .unwrap();
+    assert_eq!(proposal.tally(&suite.app).unwrap().no, Uint128::zero());
+    carol_proxy
+        .vote(&mut suite.app, &carol, proposal.addr(), VoteOption::Yes, 3)
+        .unwrap();
+    assert_eq!(
+        proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
+    );
+
+    // the retracted yes votes are no shares of the joining fee
+    proposal
+        .join(&mut suite.app, &charlie, &coins(32, ATOM))
+        .unwrap();
+    for (proxy, reward) in [(&alice_proxy, 12), (&bob_proxy, 8), (&carol_proxy, 12)] {
+        assert_eq!(
+            proxy.withdrawable(&suite.app).unwrap(),
+            WithdrawableResp {
+                funds: vec![coin(reward, ATOM)]
+            }
+        );
+    }
+    suite.assert_supply_invariant();
+}
+
+#[test]
+fn delegated_vote_tokens_vote_with_the_delegate_and_share_its_rewards() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+    let charlie = Addr::unchecked("charlie");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob", "carol"])
+        .with_pool_part(15)
+        .with_joining_fee(&coins(22, ATOM))
+        .with_initial_votes(6)
+        .with_funds("charlie", &coins(22, ATOM))
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+    let carol_proxy = suite.proxy(&carol);
+
+    let err = bob_proxy
+        .delegate(&mut suite.app, &bob, &bob, 1)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::SelfDelegation
+    ));
+    let err = bob_proxy
+        .delegate(&mut suite.app, &bob, &charlie, 1)
+        .unwrap_err();
+    assert!(matches!(err.downcast().unwrap(), ContractError::NotAMember));
+
+    // alice lends 4 of her 5 vote tokens to bob and takes 1 back
+    alice_proxy
+        .delegate(&mut suite.app, &alice, &bob, 4)
+        .unwrap();
+    let err = alice_proxy
+        .undelegate(&mut suite.app, &alice, &bob, 5)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::InsufficientDelegation { delegated } if delegated == Uint128::new(4)
+    ));
+    alice_proxy
+        .undelegate(&mut suite.app, &alice, &bob, 1)
+        .unwrap();
+
+    assert_eq!(suite.vote_balance(alice_proxy.addr()), 2);
+    assert_eq!(suite.vote_balance(bob_proxy.addr()), 8);
+    assert_eq!(
+        suite
+            .membership
+            .voting_power_at_height(&suite.app, &bob, None)
+            .unwrap()
+            .power,
+        Uint128::new(8)
+    );
+    let alice_delegations = suite.membership.delegations(&suite.app, &alice).unwrap();
+    assert_eq!(
+        alice_delegations.delegated,
+        vec![DelegationResp {
+            owner: bob.to_string(),
+            proxy: bob_proxy.addr().to_string(),
+            amount: Uint128::new(3),
+        }]
+    );
+    assert!(alice_delegations.received.is_empty());
+    assert_eq!(
+        suite
+            .membership
+            .delegations(&suite.app, &bob)
+            .unwrap()
+            .received,
+        vec![DelegationResp {
+            owner: alice.to_string(),
+            proxy: alice_proxy.addr().to_string(),
+            amount: Uint128::new(3),
+        }]
+    );
+    suite.next_block();
+
+    let proposal = suite.propose_member(&carol, 3, &charlie);
+
+    // bob votes with his own and the delegated vote tokens, 7 out of 15 does not pass yet
+    bob_proxy
+        .vote(&mut suite.app, &bob, proposal.addr(), VoteOption::Yes, 4)
+        .unwrap();
+    bob_proxy
+        .retract(&mut suite.app, &bob, proposal.addr(), 2)
+        .unwrap();
+    bob_proxy
+        .vote(&mut suite.app, &bob, proposal.addr(), VoteOption::Yes, 6)
+        .unwrap();
+    assert_eq!(
+        proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
+    );
+
+    // 8 yes vote tokens of bob are 5 of his own and 3 of alice
+    proposal
+        .join(&mut suite.app, &charlie, &coins(22, ATOM))
+        .unwrap();
+    for (proxy, reward) in [(&alice_proxy, 6), (&bob_proxy, 10), (&carol_proxy, 6)] {
+        assert_eq!(
+            proxy.withdrawable(&suite.app).unwrap(),
+            WithdrawableResp {
+                funds: vec![coin(reward, ATOM)]
+            }
+        );
+    }
+    alice_proxy.withdraw(&mut suite.app, &alice, None).unwrap();
+    assert_eq!(
+        suite.app.wrap().query_balance(&alice, ATOM).unwrap(),
+        coin(6, ATOM)
+    );
+
+    // bob spent the delegated vote tokens in the joined proposal, which cleared the delegation
+    let err = alice_proxy
+        .undelegate(&mut suite.app, &alice, &bob, 3)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::InsufficientDelegation { delegated } if delegated.is_zero()
+    ));
+    let alice_delegations = suite.membership.delegations(&suite.app, &alice).unwrap();
+    assert!(alice_delegations.delegated.is_empty());
+    assert!(suite
+        .membership
+        .delegations(&suite.app, &bob)
+        .unwrap()
+        .received
+        .is_empty());
+
+    // vote tokens delegated to a member who leaves go back to the delegator
+    carol_proxy
+        .delegate(&mut suite.app, &carol, &bob, 2)
+        .unwrap();
+    bob_proxy.leave(&mut suite.app, &bob, &[]).unwrap();
+
+    assert_eq!(suite.vote_balance(carol_proxy.addr()), 2);
+    assert_eq!(
+        suite
+            .membership
+            .voting_power_at_height(&suite.app, &carol, None)
+            .unwrap()
+            .power,
+        Uint128::new(2)
+    );
+    assert!(suite
+        .membership
+        .delegations(&suite.app, &carol)
+        .unwrap()
+        .delegated
+        .is_empty());
+
+    // vote tokens of a member who leaves are taken back from its delegates and burned
+    let circulating = suite
+        .distribution
+        .total_vote_tokens_in_circulation(&suite.app);
+    alice_proxy
+        .delegate(&mut suite.app, &alice, &carol, 1)
+        .unwrap();
+    alice_proxy.leave(&mut suite.app, &alice, &[]).unwrap();
+
+    assert_eq!(suite.vote_balance(carol_proxy.addr()), 2);
+    assert_eq!(
+        suite
+            .membership
+            .voting_power_at_height(&suite.app, &carol, None)
+            .unwrap()
+            .power,
+        Uint128::new(2)
+    );
+    assert_eq!(
+        suite
+            .distribution
+            .total_vote_tokens_in_circulation(&suite.app),
+        suite.vote_coin(circulating.amount.u128() - 2)
+    );
+    suite.assert_supply_invariant();
+}
+
+#[test]
+fn delegated_vote_tokens_escrowed_by_the_delegate_go_back_once_refunded() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+
+    let mut suite = SuiteBuilder::new()
+        .with_members(&["alice", "bob", "carol"])
+        .with_pool_part(15)
+        .with_joining_fee(&coins(22, ATOM))
+        .with_initial_votes(6)
+        .build();
+
+    let alice_proxy = suite.proxy(&alice);
+    let bob_proxy = suite.proxy(&bob);
+    let carol_proxy = suite.proxy(&carol);
+
+    // alice lends 4 of her 5 vote tokens to bob, who escrows all his own and 2 of them
+    alice_proxy
+        .delegate(&mut suite.app, &alice, &bob, 4)
+        .unwrap();
+    suite.next_block();
+
+    let proposal_data = carol_proxy
+        .propose_generic(&mut suite.app, &carol, 1, "Nothing", "Does nothing", vec![])
+        .unwrap()
+        .unwrap();
+    let proposal = ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
+    bob_proxy
+        .vote(&mut suite.app, &bob, proposal.addr(), VoteOption::No, 7)
+        .unwrap();
+
+    // only the 2 bob still holds go back, the escrowed ones stay delegated
+    let resp = alice_proxy
+        .undelegate(&mut suite.app, &alice, &bob, 4)
+        .unwrap();
+    assert!(resp.events.iter().any(|event| event.ty == "wasm"
+        && event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "returned" && attr.value == "2")));
+    for (owner, proxy, vote_tokens) in [(&alice, &alice_proxy, 3), (&bob, &bob_proxy, 0)] {
+        assert_eq!(suite.vote_balance(proxy.addr()), vote_tokens);
+        assert_eq!(
+            suite
+                .membership
+                .voting_power_at_height(&suite.app, owner, None)
+                .unwrap()
+                .power,
+            Uint128::new(vote_tokens)
+        );
+    }
+    assert_eq!(
+        suite
+            .membership
+            .delegations(&suite.app, &bob)
+            .unwrap()
+            .received,
+        vec![DelegationResp {
+            owner: alice.to_string(),
+            proxy: alice_proxy.addr().to_string(),
+            amount: Uint128::new(2),
+        }]
+    );
+    let err = alice_proxy
+        .undelegate(&mut suite.app, &alice, &bob, 3)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::InsufficientDelegation { delegated } if delegated == Uint128::new(2)
+    ));
+
+    // the escrow refunded to bob on close can be undelegated, his own vote tokens stay his
+    suite.advance_blocks(100);
+    proposal.close(&mut suite.app, &carol).unwrap();
+    alice_proxy
+        .undelegate(&mut suite.app, &alice, &bob, 2)
+        .unwrap();
+
+    for (owner, proxy) in [
+        (&alice, &alice_proxy),
+        (&bob, &bob_proxy),
+        (&carol, &carol_proxy),
+    ] {
+        assert_eq!(suite.vote_balance(proxy.addr()), 5);
+        assert_eq!(
+            suite
+                .membership
+                .voting_power_at_height(&suite.app, owner, None)
+                .unwrap()
+                .power,
+            Uint128::new(5)
+        );
+    }
+    assert!(suite
+        .membership
+        .delegations(&suite.app, &alice)
+        .unwrap()
+        .delegated
+        .is_empty());
+    suite.assert_supply_invariant();
+
+    // carol escrows 2 of the 3 alice lends her and leaves, alice gets the 1 she still held
+    alice_proxy
+        .delegate(&mut suite.app, &alice, &carol, 3)
+        .unwrap();
+    suite.next_block();
+
+    let proposal_data = bob_proxy
+        .propose_generic(&mut suite.app, &bob, 1, "Nothing", "Does nothing", vec![])
+        .unwrap()
+        .unwrap();
+    let proposal = ProposalContract::from_addr(Addr::unchecked(proposal_data.proposal_addr));
+    carol_proxy
+        .vote(&mut suite.app, &carol, proposal.addr(), VoteOption::No, 7)
+        .unwrap();
+    carol_proxy.leave(&mut suite.app, &carol, &[]).unwrap();
+
+    assert_eq!(suite.vote_balance(alice_proxy.addr()), 3);
+    assert_eq!(
+        suite
+            .membership
+            .delegations(&suite.app, &alice)
+            .unwrap()
+            .delegated,
+        vec![DelegationResp {
+            owner: carol.to_string(),
+            proxy: carol_proxy.addr().to_string(),
+            amount: Uint128::new(2),
+        }]
+    );
+
+    // the escrowed ones go back to alice when the proposal closes, the rest of carol's is burned
+    suite.advance_blocks(100);
+    proposal.close(&mut suite.app, &bob).unwrap();
+
+    assert_eq!(suite.vote_balance(alice_proxy.addr()), 5);
+    assert_eq!(
+        suite
+            .membership
+            .voting_power_at_height(&suite.app, &alice, None)
+            .unwrap()
+            .power,
+        Uint128::new(5)
+    );
+    assert!(suite
+        .membership
+        .delegations(&suite.app, &alice)
+        .unwrap()
+        .delegated
+        .is_empty());
     assert_eq!(
-        distribution_contract.total_vote_tokens_in_circulation(&app),
-        coin(13, VOTE_DENOM)
+        suite
+            .distribution
+            .total_vote_tokens_in_circulation(&suite.app),
+        suite.vote_coin(10)
     );
+    suite.assert_supply_invariant();
 }
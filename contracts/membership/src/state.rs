@@ -1,36 +1,68 @@
 use std::collections::HashMap;
 
+use common::vote_token::VoteAsset;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Decimal, Empty};
+use cosmwasm_std::{Addr, Coin, Decimal, Empty, Timestamp, Uint128};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, UniqueIndex};
+use cw_utils::Duration;
 
 #[cw_serde]
 pub struct Config {
     pub proxy_code_id: u64,
     pub proposal_code_id: u64,
     pub distribution_contract: Addr,
-    pub joining_fee: Coin,
+    pub reward_denoms: Vec<String>,
+    pub joining_fee: Vec<Coin>,
+    pub initial_votes: Uint128,
     pub initial_vote_token_distribution_part: Decimal,
+    pub voting_period: Duration,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub vote_token: VoteAsset,
+}
+
+#[cw_serde]
+pub struct Member {
+    pub owner: Addr,
+    pub joined_height: u64,
+    pub joined_time: Timestamp,
+    // proposal which admitted the member, none for initial members
+    pub proposal: Option<Addr>,
+    pub initial_votes: Coin,
+}
+
+// generic proposal whose messages are being executed, kept until the last of them replied
+#[cw_serde]
+pub struct ExecutingProposal {
+    pub proposal: Addr,
+    pub pending: u32,
+}
+
+// join details kept between proxy instantiation and its reply
+#[cw_serde]
+pub struct PendingMember {
+    pub proposal: Option<Addr>,
+    pub initial_votes: Coin,
 }
 
 pub struct MembersIndexes<'a> {
-    pub owner: UniqueIndex<'a, Addr, Addr, Addr>,
+    pub owner: UniqueIndex<'a, Addr, Member, Addr>,
 }
 
-impl<'a> IndexList<Addr> for MembersIndexes<'a> {
-    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Addr>> + '_> {
-        let v: [&dyn Index<Addr>; 1] = [&self.owner];
+impl<'a> IndexList<Member> for MembersIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Member>> + '_> {
+        let v: [&dyn Index<Member>; 1] = [&self.owner];
         Box::new(v.into_iter())
     }
 }
 
-// proxy => owner
+// proxy => member
 //
 // secondary indexes:
 // * owner
-pub fn members() -> IndexedMap<'static, &'static Addr, Addr, MembersIndexes<'static>> {
+pub fn members() -> IndexedMap<'static, &'static Addr, Member, MembersIndexes<'static>> {
     let indexes = MembersIndexes {
-        owner: UniqueIndex::new(|owner| owner.clone(), "members__owner"),
+        owner: UniqueIndex::new(|member| member.owner.clone(), "members__owner"),
     };
     IndexedMap::new("members", indexes)
 }
@@ -62,4 +94,46 @@ pub fn candidates() -> IndexedMap<'static, &'static Addr, Addr, CandidatesIndexe
     IndexedMap::new("candidates", indexes)
 }
 
+pub struct RemovalsIndexes<'a> {
+    pub proposal: UniqueIndex<'a, Addr, Addr, Addr>,
+}
+
+impl<'a> IndexList<Addr> for RemovalsIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Addr>> + '_> {
+        let v: [&dyn Index<Addr>; 1] = [&self.proposal];
+        Box::new(v.into_iter())
+    }
+}
+
+// member owner => open removal proposal
+//
+// secondary indexes:
+// * proposal
+pub fn removals() -> IndexedMap<'static, &'static Addr, Addr, RemovalsIndexes<'static>> {
+    let indexes = RemovalsIndexes {
+        proposal: UniqueIndex::new(|proposal| proposal.clone(), "removals__proposal"),
+    };
+    IndexedMap::new("removals", indexes)
+}
+
+// proposal-addr of open generic proposals
+pub const GENERIC_PROPOSALS: Map<&Addr, Empty> = Map::new("generic_proposals");
+
+// generic proposal whose messages are being executed, their failures are reported for it
+pub const EXECUTING_PROPOSAL: Item<ExecutingProposal> = Item::new("executing_proposal");
+
+// (proposal-addr, voter proxy) => vote tokens delegated to the voter among its escrow in the
+// proposal, the voter's own vote tokens are escrowed first
+pub const DELEGATED_ESCROW: Map<(&Addr, &Addr), Uint128> = Map::new("delegated_escrow");
+// voter proxy => delegated vote tokens it has escrowed in proposals, its delegators get back only
+// the rest of their delegations until they are refunded
+pub const ESCROWED_DELEGATIONS: Map<&Addr, Uint128> = Map::new("escrowed_delegations");
+
+// proposal-addr of every proposal instantiated by membership
+pub const PROPOSALS: Map<&Addr, Empty> = Map::new("proposals");
+
 pub const AWAITING_INITIAL_RESPS: Item<u64> = Item::new("awaiting_initial_resps");
+pub const PENDING_MEMBER: Item<PendingMember> = Item::new("pending_member");
+
+// set while the pool of a reserve held vote token is yet to be funded
+pub const AWAITING_POOL: Item<Empty> = Item::new("awaiting_pool");
@@ -1,16 +1,22 @@
-use common::keys::VOTE_DENOM;
-use cosmwasm_std::{coin, Addr, DepsMut, Env, MessageInfo, Reply, Response};
-use cw2::set_contract_version;
-use cw_utils::must_pay;
+use cosmwasm_std::{
+    ensure, from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply,
+    Response, StdResult,
+};
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
 
 use crate::{
     error::ContractError,
     msg::ExecMsg,
-    msg::InstantiateMsg,
-    state::{Config, CONFIG, OWNER, VOTER_TOKENS, IS_PASSED},
+    msg::{InstantiateMsg, MigrateMsg, ProposalKind as ProposalKindMsg, QueryMsg, VoteOption},
+    state::{
+        Config, ProposalKind, Tally, CONFIG, EXPIRATION, IS_CLOSED, IS_JOINED, IS_PASSED, KIND,
+        OWNER, PROPOSER, START_HEIGHT, TALLY, VOTER_TOKENS,
+    },
 };
 
 mod exec;
+mod query;
 mod reply;
 
 const MEMBER_JOINED_REPLY_ID: u64 = 1;
@@ -27,36 +33,108 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let proposer = deps.api.addr_validate(&msg.proposer)?;
-    let owner = deps.api.addr_validate(&msg.proposed_owner)?;
-    let vote_amount = must_pay(&info, VOTE_DENOM)?;
+    let vote_amount = msg.escrow;
 
     // addresses are trusted as they come from membership contract
     let distribution_contract = Addr::unchecked(msg.distribution_contract);
     let membership_contract = Addr::unchecked(&msg.membership_contract);
 
-    OWNER.save(deps.storage, &owner)?;
+    let mut resp = Response::new()
+        .add_attribute("action", "new_proposal")
+        .add_attribute("sender", info.sender.as_str());
+
+    let kind = match msg.kind {
+        ProposalKindMsg::Member {
+            proposed_owner,
+            joining_fee,
+        } => {
+            let owner = deps.api.addr_validate(&proposed_owner)?;
+            OWNER.save(deps.storage, &owner)?;
+            resp = resp.add_attribute("owner", owner.as_str());
+            ProposalKind::Member { joining_fee }
+        }
+        ProposalKindMsg::Generic {
+            title,
+            description,
+            msgs,
+        } => {
+            resp = resp.add_attribute("title", title.as_str());
+            ProposalKind::Generic {
+                title,
+                description,
+                msgs,
+            }
+        }
+        ProposalKindMsg::Removal { member } => {
+            let member = deps.api.addr_validate(&member)?;
+            resp = resp.add_attribute("member", member.as_str());
+            ProposalKind::Removal { member }
+        }
+    };
+
+    KIND.save(deps.storage, &kind)?;
+    PROPOSER.save(deps.storage, &proposer)?;
 
     CONFIG.save(
         deps.storage,
         &Config {
             distribution_contract,
             membership_contract,
-            joining_fee: msg.joining_fee,
+            quorum: msg.quorum,
+            threshold: msg.threshold,
+            vote_token: msg.vote_token.clone(),
         },
     )?;
 
+    // proposer escrow counts as a yes vote
     VOTER_TOKENS.save(
         deps.storage,
-        &proposer,
-        &coin(vote_amount.u128(), VOTE_DENOM),
+        (VoteOption::Yes.as_str(), &proposer),
+        &msg.vote_token.coin(vote_amount),
     )?;
 
+    let mut tally = Tally::default();
+    tally.add(VoteOption::Yes, vote_amount);
+    TALLY.save(deps.storage, &tally)?;
+
     IS_PASSED.save(deps.storage, &false)?;
+    IS_JOINED.save(deps.storage, &false)?;
+    IS_CLOSED.save(deps.storage, &false)?;
+    EXPIRATION.save(deps.storage, &msg.expiration)?;
+    START_HEIGHT.save(deps.storage, &env.block.height)?;
+
+    Ok(resp
+        .add_attribute("expiration", msg.expiration.to_string())
+        .add_attribute("start_height", env.block.height.to_string()))
+}
+
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    ensure!(
+        stored.contract == CONTRACT_NAME,
+        ContractError::MigrationInvalidContract {
+            name: stored.contract
+        }
+    );
+
+    let stored_version: Version = stored.version.parse()?;
+    let version: Version = CONTRACT_VERSION.parse()?;
+
+    ensure!(
+        stored_version <= version,
+        ContractError::MigrationDowngrade {
+            version: stored.version
+        }
+    );
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let resp = Response::new()
-        .add_attribute("action", "new_proposal")
-        .add_attribute("sender", info.sender.as_str())
-        .add_attribute("owner", owner.as_str());
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION);
+
     Ok(resp)
 }
 
@@ -69,15 +147,45 @@ pub fn execute(
     use ExecMsg::*;
 
     match msg {
+        Receive(wrapper) => {
+            let config = CONFIG.load(deps.storage)?;
+            let (info, msg) = config
+                .vote_token
+                .receive(&info, wrapper)
+                .ok_or(ContractError::Unauthorized)?;
+            execute(deps, env, info, from_json(msg)?)
+        }
         Pass {} => exec::pass(deps, env, info),
-        Vote {} => exec::vote(deps, env, info),
+        Vote { option } => exec::vote(deps, env, info, option),
+        Retract { amount } => exec::retract(deps, env, info, amount),
         Join {} => exec::join(deps, env, info),
+        Close {} => exec::close(deps, env, info),
     }
 }
 
 pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id {
-        MEMBER_JOINED_REPLY_ID => reply::member_joined(reply.result.into_result()),
+        MEMBER_JOINED_REPLY_ID => reply::member_joined(deps, reply.result.into_result()),
         id => Err(ContractError::UnrecognizedReplyId(id)),
     }
 }
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    use QueryMsg::*;
+
+    match msg {
+        Status {} => to_json_binary(&query::status(deps, env)?),
+        Candidate {} => to_json_binary(&query::candidate(deps)?),
+        Proposer {} => to_json_binary(&query::proposer(deps)?),
+        JoiningFee {} => to_json_binary(&query::joining_fee(deps)?),
+        TotalYesTokens {} => to_json_binary(&query::total_yes_tokens(deps)?),
+        Tally {} => to_json_binary(&query::tally(deps)?),
+        Threshold {} => to_json_binary(&query::threshold(deps)?),
+        GenericProposal {} => to_json_binary(&query::generic_proposal(deps)?),
+        Voters {
+            option,
+            start_after,
+            limit,
+        } => to_json_binary(&query::voters(deps, option, start_after, limit)?),
+    }
+}
@@ -1,21 +1,24 @@
-use std::collections::HashMap;
-
-use common::keys::VOTE_DENOM;
 use cosmwasm_std::{
-    coin, coins, ensure, to_binary, Addr, BankMsg, DepsMut, Env, MessageInfo, Response, StdResult,
-    SubMsg, WasmMsg,
+    coin, coins, ensure, to_json_binary, Addr, BankMsg, DepsMut, Env, MessageInfo, Response,
+    StdResult, SubMsg, Uint128, WasmMsg,
 };
-use cw_utils::must_pay;
+use cw_utils::{must_pay, nonpayable, one_coin};
 
 use common::msg::membership::{ExecMsg as MembershipExecMsg, IsProposedMemberResp, OwnerProxyResp};
-use common::msg::membership::{IsMemberResp, QueryMsg::IsProposedMember, QueryMsg::OwnerProxy};
-use distribution::msg::ExecMsg as DistributionExecMsg;
+use common::msg::membership::{
+    IsMemberResp, QueryMsg::IsMember, QueryMsg::IsProposedMember, QueryMsg::MemberByProxy,
+};
+use common::msg::{ProposalMemberData, VoterTokens};
+use distribution::msg::{
+    ExecMsg as DistributionExecMsg, QueryMsg as DistributionQueryMsg, VotingPowerResp,
+};
 
 use crate::contract::MEMBER_JOINED_REPLY_ID;
-use crate::state::VOTER_TOKENS;
+use crate::msg::VoteOption;
+use crate::state::{START_HEIGHT, TALLY, VOTER_TOKENS};
 use crate::{
     error::ContractError,
-    state::{CONFIG, IS_PASSED, OWNER},
+    state::{ProposalKind, CONFIG, EXPIRATION, IS_CLOSED, IS_JOINED, IS_PASSED, KIND, OWNER},
 };
 
 pub fn pass(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
@@ -27,78 +30,272 @@ pub fn pass(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, Cont
     );
 
     IS_PASSED.save(deps.storage, &true)?;
-    let owner = OWNER.load(deps.storage)?;
 
     let resp = Response::new()
         .add_attribute("action", "pass_proposal")
-        .add_attribute("sender", info.sender.as_str())
-        .add_attribute("owner", owner.into_string());
+        .add_attribute("sender", info.sender.as_str());
+
+    // escrow of other proposals is done with, it goes back to the distribution reserve
+    let ProposalKind::Member { .. } = KIND.load(deps.storage)? else {
+        let escrow = config
+            .vote_token
+            .balance(&deps.querier, env.contract.address)?;
+        if escrow.is_zero() {
+            return Ok(resp);
+        }
+        let msg = config
+            .vote_token
+            .transfer(config.distribution_contract, escrow)?;
+        return Ok(resp.add_message(msg));
+    };
+
+    let owner = OWNER.load(deps.storage)?;
+
+    let data = ProposalMemberData {
+        owner_addr: owner.to_string(),
+        proposal_addr: env.contract.address.into_string(),
+    };
+
+    let resp = resp
+        .add_attribute("owner", owner.into_string())
+        .set_data(to_json_binary(&data)?);
 
     Ok(resp)
 }
 
-pub fn vote(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    let vote_amount = must_pay(&info, VOTE_DENOM)?;
+pub fn vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    option: VoteOption,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let vote_amount = must_pay(&info, &config.vote_token.denom())?;
 
     let sender = info.sender;
-    let config = CONFIG.load(deps.storage)?;
     let is_passed = IS_PASSED.load(deps.storage)?;
-    let owner = OWNER.load(deps.storage)?;
 
-   
-    ensure!(!is_passed, ContractError::VoteRejectedProposalWasPassedEarlier);
+    ensure!(
+        !is_passed,
+        ContractError::VoteRejectedProposalWasPassedEarlier
+    );
+    ensure!(
+        !EXPIRATION.load(deps.storage)?.is_expired(&env.block),
+        ContractError::VoteRejectedProposalExpired
+    );
 
-    let sender_proxy_resp: OwnerProxyResp = deps.querier.query_wasm_smart(
-        config.membership_contract,
-        &OwnerProxy {
-            owner: sender.to_string(),
+    // votes are cast by member proxies out of the vote tokens they hold
+    let member: OwnerProxyResp = deps.querier.query_wasm_smart(
+        config.membership_contract.clone(),
+        &MemberByProxy {
+            proxy: sender.to_string(),
         },
     )?;
 
-    let sender_proxy = Addr::unchecked(sender_proxy_resp.proxy);
-
-    VOTER_TOKENS.update(deps.storage, &sender_proxy, |votes| -> StdResult<_> {
-        let votes = votes.map_or_else(
-            || coin(vote_amount.u128(), VOTE_DENOM),
-            |c| coin((c.amount + vote_amount).u128(), c.denom),
+    // a voter sticks to a single option
+    for other in [VoteOption::Yes, VoteOption::No, VoteOption::Abstain] {
+        ensure!(
+            other == option || !VOTER_TOKENS.has(deps.storage, (other.as_str(), &sender)),
+            ContractError::VoteRejectedOptionMismatch {
+                option: other.as_str().to_owned()
+            }
         );
-        Ok(votes)
+    }
+
+    // vote tokens bought or minted after the proposal started do not count, those delegated to
+    // the voter before do
+    let power: VotingPowerResp = deps.querier.query_wasm_smart(
+        &config.distribution_contract,
+        &DistributionQueryMsg::VotingPower {
+            address: member.owner,
+            height: Some(START_HEIGHT.load(deps.storage)?),
+        },
+    )?;
+    let voted = VOTER_TOKENS
+        .may_load(deps.storage, (option.as_str(), &sender))?
+        .map(|votes| votes.amount)
+        .unwrap_or_default();
+    ensure!(
+        voted + vote_amount <= power.power,
+        ContractError::VoteRejectedInsufficientPower { power: power.power }
+    );
+
+    VOTER_TOKENS.update(
+        deps.storage,
+        (option.as_str(), &sender),
+        |votes| -> StdResult<_> {
+            let votes = votes.map_or_else(
+                || config.vote_token.coin(vote_amount),
+                |c| coin((c.amount + vote_amount).u128(), c.denom),
+            );
+            Ok(votes)
+        },
+    )?;
+
+    TALLY.update(deps.storage, |mut tally| -> StdResult<_> {
+        tally.add(option, vote_amount);
+        Ok(tally)
     })?;
+
+    let voter_proxy = sender.to_string();
+    let amount = vote_amount;
+
+    let (mem_msg, resp) = match KIND.load(deps.storage)? {
+        ProposalKind::Member { .. } => {
+            let owner = OWNER.load(deps.storage)?;
+            let resp = Response::new()
+                .add_attribute("action", "vote_member_proposal")
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("option", option.as_str())
+                .add_attribute("owner", owner.into_string());
+            let msg = MembershipExecMsg::VoteMemberProposal {
+                voter_proxy,
+                amount,
+            };
+            (msg, resp)
+        }
+        ProposalKind::Generic { .. } => {
+            let resp = Response::new()
+                .add_attribute("action", "vote_generic_proposal")
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("option", option.as_str());
+            let msg = MembershipExecMsg::VoteGenericProposal {
+                voter_proxy,
+                amount,
+            };
+            (msg, resp)
+        }
+        ProposalKind::Removal { member } => {
+            let resp = Response::new()
+                .add_attribute("action", "vote_removal_proposal")
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("option", option.as_str())
+                .add_attribute("member", member.into_string());
+            let msg = MembershipExecMsg::VoteRemovalProposal {
+                voter_proxy,
+                amount,
+            };
+            (msg, resp)
+        }
+    };
+
+    // membership re-evaluates the tally and passes the proposal once it crosses the threshold
+    let mem_msg = WasmMsg::Execute {
+        contract_addr: config.membership_contract.into_string(),
+        msg: to_json_binary(&mem_msg)?,
+        funds: vec![],
+    };
+
+    Ok(resp.add_message(mem_msg))
+}
+
+pub fn retract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
     let config = CONFIG.load(deps.storage)?;
+    let sender = info.sender;
+
+    ensure!(
+        !IS_PASSED.load(deps.storage)?,
+        ContractError::RetractRejectedProposalWasPassedEarlier
+    );
+    ensure!(
+        !EXPIRATION.load(deps.storage)?.is_expired(&env.block),
+        ContractError::RetractRejectedProposalExpired
+    );
 
-    let mem_msg = MembershipExecMsg::VoteMemberProposal {
-        voter: owner.to_string(),
-        voter_proxy: sender_proxy.to_string(),
+    // voted by the sender proxy, on a single option
+    let mut votes = None;
+    for option in [VoteOption::Yes, VoteOption::No, VoteOption::Abstain] {
+        if let Some(voted) = VOTER_TOKENS.may_load(deps.storage, (option.as_str(), &sender))? {
+            votes = Some((option, voted));
+        }
+    }
+
+    let (option, voted) = votes.ok_or(ContractError::RetractRejectedExceedsVote {
+        voted: Uint128::zero(),
+    })?;
+    ensure!(
+        !amount.is_zero() && amount <= voted.amount,
+        ContractError::RetractRejectedExceedsVote {
+            voted: voted.amount
+        }
+    );
+
+    let remaining = voted.amount - amount;
+    if remaining.is_zero() {
+        VOTER_TOKENS.remove(deps.storage, (option.as_str(), &sender));
+    } else {
+        VOTER_TOKENS.save(
+            deps.storage,
+            (option.as_str(), &sender),
+            &coin(remaining.u128(), voted.denom),
+        )?;
+    }
+
+    TALLY.update(deps.storage, |mut tally| -> StdResult<_> {
+        tally.sub(option, amount)?;
+        Ok(tally)
+    })?;
+
+    let refund_msg = config.vote_token.transfer(sender.clone(), amount)?;
+
+    // membership gives the voting power back and drops the joining fee shares
+    let mem_msg = MembershipExecMsg::VoteRetracted {
+        voter_proxy: sender.to_string(),
+        amount,
+        yes: option == VoteOption::Yes,
     };
     let mem_msg = WasmMsg::Execute {
         contract_addr: config.membership_contract.into_string(),
-        msg: to_binary(&mem_msg)?,
+        msg: to_json_binary(&mem_msg)?,
         funds: vec![],
     };
 
     let resp = Response::new()
-        .add_attribute("action", "vote_member_proposal")
+        .add_message(refund_msg)
+        .add_message(mem_msg)
+        .add_attribute("action", "retract_vote")
         .add_attribute("sender", sender.as_str())
-        .add_attribute("owner", owner.into_string());
+        .add_attribute("option", option.as_str())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("remaining", remaining.to_string());
 
     Ok(resp)
 }
 
 pub fn join(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let fee_paid = must_pay(&info, &config.joining_fee.denom)?;
+    let ProposalKind::Member { joining_fee } = KIND.load(deps.storage)? else {
+        return Err(ContractError::NotMemberProposal);
+    };
+    let fee_paid = one_coin(&info)?;
 
     ensure!(
-        fee_paid >= config.joining_fee.amount,
-        ContractError::JoinRejected {
-            fee: config.joining_fee
-        }
+        joining_fee
+            .iter()
+            .any(|fee| fee.denom == fee_paid.denom && fee_paid.amount >= fee.amount),
+        ContractError::JoinRejected { fee: joining_fee }
     );
 
     let sender = info.sender;
     let owner = OWNER.load(deps.storage)?;
 
     ensure!(sender == owner, ContractError::Unauthorized);
+    ensure!(
+        IS_PASSED.load(deps.storage)?,
+        ContractError::JoinRejectedProposalNotPassed
+    );
+    // expiry is also the deadline to join, the proposal can be closed afterwards
+    ensure!(
+        !EXPIRATION.load(deps.storage)?.is_expired(&env.block),
+        ContractError::JoinRejectedProposalExpired
+    );
 
     let is_proposed_member: IsProposedMemberResp = deps.querier.query_wasm_smart(
         config.membership_contract.clone(),
@@ -109,36 +306,28 @@ pub fn join(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, Cont
 
     ensure!(is_proposed_member.ok, ContractError::Unauthorized);
 
-    let vote_tokens = deps
-        .querier
-        .query_balance(env.contract.address, VOTE_DENOM)?;
+    let vote_tokens = config
+        .vote_token
+        .balance(&deps.querier, env.contract.address)?;
 
-    let mem_msg = MembershipExecMsg::NewMember {};
-    let mem_msg = WasmMsg::Execute {
-        contract_addr: config.membership_contract.into_string(),
-        msg: to_binary(&mem_msg)?,
-        funds: vec![vote_tokens.clone()],
-    };
+    let mem_msg = config.vote_token.send(
+        config.membership_contract,
+        vote_tokens,
+        &MembershipExecMsg::NewMember {},
+    )?;
 
     let mem_msg = SubMsg::reply_on_success(mem_msg, MEMBER_JOINED_REPLY_ID);
 
-    let voter_tokens: HashMap<_, _> = VOTER_TOKENS
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .into_iter()
-        .map(|votes| -> StdResult<_> {
-            let votes = votes?;
-            Ok((votes.0.to_string(), votes.1))
-        })
-        .collect::<Result<_, _>>()?;
+    // joining fee is rewarded to yes voters only, distribution got their shares as they voted
+    let tally = TALLY.load(deps.storage)?;
 
     let dis_msg = DistributionExecMsg::DistributeJoiningFee {
-        total_vote_tokens: vote_tokens,
-        voter_tokens,
+        total_vote_tokens: config.vote_token.coin(tally.yes),
     };
     let dis_msg = WasmMsg::Execute {
         contract_addr: config.distribution_contract.into_string(),
-        msg: to_binary(&dis_msg)?,
-        funds: coins(fee_paid.u128(), config.joining_fee.denom),
+        msg: to_json_binary(&dis_msg)?,
+        funds: vec![fee_paid],
     };
 
     let resp = Response::new()
@@ -150,3 +339,71 @@ pub fn join(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, Cont
 
     Ok(resp)
 }
+
+pub fn close(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure!(
+        EXPIRATION.load(deps.storage)?.is_expired(&env.block),
+        ContractError::CloseRejectedProposalNotExpired
+    );
+    // escrow of other passed proposals is released as they pass, that of a passed member
+    // proposal is kept until its candidate joins, which it no longer can
+    let is_member = matches!(KIND.load(deps.storage)?, ProposalKind::Member { .. });
+    ensure!(
+        !IS_PASSED.load(deps.storage)? || (is_member && !IS_JOINED.load(deps.storage)?),
+        ContractError::CloseRejectedProposalWasPassed
+    );
+    ensure!(
+        !IS_CLOSED.load(deps.storage)?,
+        ContractError::ProposalAlreadyClosed
+    );
+
+    IS_CLOSED.save(deps.storage, &true)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // escrowed vote tokens go back to each voter proxy, those of members who left go to
+    // distribution, membership has them burned
+    let refunds: Vec<_> = VOTER_TOKENS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|votes| -> StdResult<_> {
+            let ((_, voter_proxy), votes) = votes?;
+            Ok(VoterTokens {
+                voter: voter_proxy.into_string(),
+                amount: votes.amount,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    let refund_msgs = refunds
+        .iter()
+        .map(|refund| {
+            let is_member: IsMemberResp = deps.querier.query_wasm_smart(
+                &config.membership_contract,
+                &IsMember {
+                    addr: refund.voter.clone(),
+                },
+            )?;
+            let recipient = if is_member.ok {
+                Addr::unchecked(&refund.voter)
+            } else {
+                config.distribution_contract.clone()
+            };
+            config.vote_token.transfer(recipient, refund.amount)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mem_msg = MembershipExecMsg::ProposalClosed { refunds };
+    let mem_msg = WasmMsg::Execute {
+        contract_addr: config.membership_contract.into_string(),
+        msg: to_json_binary(&mem_msg)?,
+        funds: vec![],
+    };
+
+    let resp = Response::new()
+        .add_messages(refund_msgs)
+        .add_message(mem_msg)
+        .add_attribute("action", "close_proposal")
+        .add_attribute("sender", info.sender.as_str());
+
+    Ok(resp)
+}
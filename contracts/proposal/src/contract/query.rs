@@ -0,0 +1,146 @@
+use cosmwasm_std::{coin, Addr, Deps, Env, Order, StdError, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::{
+    msg::{
+        CandidateResp, GenericProposalResp, JoiningFeeResp, ProposalStatus, ProposerResp,
+        StatusResp, TallyResp, ThresholdResp, TotalYesTokensResp, VoteOption, VoterResp,
+        VotersResp,
+    },
+    state::{
+        ProposalKind, CONFIG, EXPIRATION, IS_CLOSED, IS_JOINED, IS_PASSED, KIND, OWNER, PROPOSER,
+        START_HEIGHT, TALLY, VOTER_TOKENS,
+    },
+};
+use distribution::msg::{QueryMsg as DistributionQueryMsg, VotingPowerResp};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub fn status(deps: Deps, env: Env) -> StdResult<StatusResp> {
+    let expiration = EXPIRATION.load(deps.storage)?;
+    let is_expired = expiration.is_expired(&env.block);
+    // a passed member proposal is joined until it expires, other proposals are done once passed
+    let is_member = matches!(KIND.load(deps.storage)?, ProposalKind::Member { .. });
+
+    let status = if IS_JOINED.load(deps.storage)? {
+        ProposalStatus::Joined
+    } else if IS_CLOSED.load(deps.storage)? {
+        ProposalStatus::Closed
+    } else if IS_PASSED.load(deps.storage)? && !(is_member && is_expired) {
+        ProposalStatus::Passed
+    } else if is_expired {
+        ProposalStatus::Expired
+    } else {
+        ProposalStatus::Open
+    };
+
+    Ok(StatusResp {
+        status,
+        expiration,
+        start_height: START_HEIGHT.load(deps.storage)?,
+    })
+}
+
+pub fn candidate(deps: Deps) -> StdResult<CandidateResp> {
+    let owner = OWNER
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("not a member proposal"))?;
+
+    Ok(CandidateResp {
+        candidate: owner.into(),
+    })
+}
+
+pub fn proposer(deps: Deps) -> StdResult<ProposerResp> {
+    let proposer = PROPOSER.load(deps.storage)?;
+
+    Ok(ProposerResp {
+        proposer: proposer.into(),
+    })
+}
+
+pub fn joining_fee(deps: Deps) -> StdResult<JoiningFeeResp> {
+    match KIND.load(deps.storage)? {
+        ProposalKind::Member { joining_fee } => Ok(JoiningFeeResp { fee: joining_fee }),
+        _ => Err(StdError::generic_err("not a member proposal")),
+    }
+}
+
+pub fn generic_proposal(deps: Deps) -> StdResult<GenericProposalResp> {
+    match KIND.load(deps.storage)? {
+        ProposalKind::Generic {
+            title,
+            description,
+            msgs,
+        } => Ok(GenericProposalResp {
+            title,
+            description,
+            msgs,
+        }),
+        _ => Err(StdError::generic_err("not a generic proposal")),
+    }
+}
+
+pub fn total_yes_tokens(deps: Deps) -> StdResult<TotalYesTokensResp> {
+    let tally = TALLY.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(TotalYesTokensResp {
+        tokens: config.vote_token.coin(tally.yes),
+    })
+}
+
+pub fn tally(deps: Deps) -> StdResult<TallyResp> {
+    let tally = TALLY.load(deps.storage)?;
+
+    Ok(TallyResp {
+        yes: tally.yes,
+        no: tally.no,
+        abstain: tally.abstain,
+    })
+}
+
+pub fn threshold(deps: Deps) -> StdResult<ThresholdResp> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let total: VotingPowerResp = deps.querier.query_wasm_smart(
+        config.distribution_contract,
+        &DistributionQueryMsg::TotalVotingPower {
+            height: Some(START_HEIGHT.load(deps.storage)?),
+        },
+    )?;
+
+    Ok(ThresholdResp {
+        total_vote_tokens_in_circulation: config.vote_token.coin(total.power),
+        quorum: config.quorum,
+        threshold: config.threshold,
+    })
+}
+
+pub fn voters(
+    deps: Deps,
+    option: VoteOption,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VotersResp> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let voters = VOTER_TOKENS
+        .prefix(option.as_str())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|votes| -> StdResult<_> {
+            let (voter, tokens) = votes?;
+            Ok(VoterResp {
+                voter: voter.into(),
+                option,
+                tokens,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(VotersResp { voters })
+}
@@ -1,9 +1,15 @@
-use cosmwasm_std::{Response, StdError, SubMsgResponse};
+use cosmwasm_std::{DepsMut, Response, StdError, SubMsgResponse};
 
-use crate::error::ContractError;
+use crate::{error::ContractError, state::IS_JOINED};
 
-pub fn member_joined(reply: Result<SubMsgResponse, String>) -> Result<Response, ContractError> {
+pub fn member_joined(
+    deps: DepsMut,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
+
+    IS_JOINED.save(deps.storage, &true)?;
+
     if let Some(data) = response.data {
         let resp = Response::new().set_data(data);
         Ok(resp)
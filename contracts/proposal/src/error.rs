@@ -1,4 +1,4 @@
-use cosmwasm_std::{Coin, StdError};
+use cosmwasm_std::{Coin, StdError, Uint128};
 use cw_utils::PaymentError;
 use thiserror::Error;
 
@@ -18,6 +18,51 @@ pub enum ContractError {
     #[error("Vote rejected as proposal was passed earlier")]
     VoteRejectedProposalWasPassedEarlier,
 
-    #[error("Pay joining fee {fee}")]
-    JoinRejected { fee: Coin },
+    #[error("Vote rejected as voter already voted {option}")]
+    VoteRejectedOptionMismatch { option: String },
+
+    #[error("Vote rejected as it exceeds the voting power of {power} at the start height")]
+    VoteRejectedInsufficientPower { power: Uint128 },
+
+    #[error("Vote rejected as proposal has expired")]
+    VoteRejectedProposalExpired,
+
+    #[error("Retract rejected as proposal was passed earlier")]
+    RetractRejectedProposalWasPassedEarlier,
+
+    #[error("Retract rejected as proposal has expired")]
+    RetractRejectedProposalExpired,
+
+    #[error("Retract rejected as it exceeds the {voted} vote tokens voted")]
+    RetractRejectedExceedsVote { voted: Uint128 },
+
+    #[error("Close rejected as proposal has not expired yet")]
+    CloseRejectedProposalNotExpired,
+
+    #[error("Close rejected as proposal was passed")]
+    CloseRejectedProposalWasPassed,
+
+    #[error("Proposal is already closed")]
+    ProposalAlreadyClosed,
+
+    #[error("Join rejected as proposal is not passed yet")]
+    JoinRejectedProposalNotPassed,
+
+    #[error("Join rejected as proposal has expired")]
+    JoinRejectedProposalExpired,
+
+    #[error("Not a member proposal")]
+    NotMemberProposal,
+
+    #[error("Pay joining fee, one of {fee:?}")]
+    JoinRejected { fee: Vec<Coin> },
+
+    #[error("{0}")]
+    Semver(#[from] semver::Error),
+
+    #[error("Cannot migrate from contract {name}")]
+    MigrationInvalidContract { name: String },
+
+    #[error("Cannot migrate from newer version {version}")]
+    MigrationDowngrade { version: String },
 }
@@ -1,22 +1,174 @@
+use common::vote_token::VoteAsset;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Coin, CosmosMsg, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_utils::Expiration;
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub proposer: String,
-    pub proposed_owner: String,
     pub distribution_contract: String,
     pub membership_contract: String,
-    pub joining_fee: Coin,
+    pub kind: ProposalKind,
+    pub expiration: Expiration,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub vote_token: VoteAsset,
+    /// proposer vote tokens, membership transfers them once the proposal is instantiated
+    pub escrow: Uint128,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
+#[cw_serde]
+pub enum ProposalKind {
+    Member {
+        proposed_owner: String,
+        /// the candidate pays any one of them
+        joining_fee: Vec<Coin>,
+    },
+    Generic {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg>,
+    },
+    Removal {
+        member: String,
+    },
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+}
+
+impl VoteOption {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VoteOption::Yes => "yes",
+            VoteOption::No => "no",
+            VoteOption::Abstain => "abstain",
+        }
+    }
 }
 
 #[cw_serde]
 pub enum ExecMsg {
+    /// cw20 vote tokens sent along with a vote
+    Receive(Cw20ReceiveMsg),
     Pass {},
-    Vote {},
+    /// sent by a member proxy along with the vote tokens
+    Vote {
+        option: VoteOption,
+    },
+    /// Takes back part or all of the vote of the sender proxy while the proposal is open
+    Retract {
+        amount: Uint128,
+    },
     Join {},
+    Close {},
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
-pub enum QueryMsg {}
+pub enum QueryMsg {
+    #[returns(StatusResp)]
+    Status {},
+    #[returns(CandidateResp)]
+    Candidate {},
+    #[returns(ProposerResp)]
+    Proposer {},
+    #[returns(JoiningFeeResp)]
+    JoiningFee {},
+    #[returns(TotalYesTokensResp)]
+    TotalYesTokens {},
+    #[returns(TallyResp)]
+    Tally {},
+    #[returns(ThresholdResp)]
+    Threshold {},
+    #[returns(GenericProposalResp)]
+    GenericProposal {},
+    #[returns(VotersResp)]
+    Voters {
+        option: VoteOption,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub enum ProposalStatus {
+    Open,
+    Passed,
+    Joined,
+    Expired,
+    Closed,
+}
+
+#[cw_serde]
+pub struct StatusResp {
+    pub status: ProposalStatus,
+    pub expiration: Expiration,
+    /// votes are counted against the voting power at the beginning of this height
+    pub start_height: u64,
+}
+
+#[cw_serde]
+pub struct CandidateResp {
+    pub candidate: String,
+}
+
+#[cw_serde]
+pub struct ProposerResp {
+    pub proposer: String,
+}
+
+#[cw_serde]
+pub struct JoiningFeeResp {
+    pub fee: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct TotalYesTokensResp {
+    pub tokens: Coin,
+}
+
+#[cw_serde]
+pub struct TallyResp {
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+}
+
+#[cw_serde]
+pub struct ThresholdResp {
+    /// vote tokens in circulation at the start height
+    pub total_vote_tokens_in_circulation: Coin,
+    /// minimum share of vote tokens in circulation that must vote
+    pub quorum: Decimal,
+    /// share of yes out of yes and no votes that must be exceeded
+    pub threshold: Decimal,
+}
+
+#[cw_serde]
+pub struct GenericProposalResp {
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg>,
+}
+
+#[cw_serde]
+pub struct VoterResp {
+    pub voter: String,
+    pub option: VoteOption,
+    pub tokens: Coin,
+}
+
+#[cw_serde]
+pub struct VotersResp {
+    pub voters: Vec<VoterResp>,
+}
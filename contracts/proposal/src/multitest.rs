@@ -1,11 +1,15 @@
 use anyhow::{Ok, Result as AnyResult};
 use common::msg::{ProposalMemberData, ProxyMemberData};
-use cosmwasm_std::{from_binary, Addr, Coin, Decimal};
-use cw_multi_test::{App, ContractWrapper, Executor};
+use common::multitest::App;
+use cosmwasm_std::{from_json, Addr, Coin, Uint128};
+use cw_multi_test::{AppResponse, ContractWrapper, Executor};
 use cw_utils::parse_execute_response_data;
 
-use crate::msg::{ExecMsg, InstantiateMsg};
-use crate::{execute, instantiate, query, reply};
+use crate::msg::{
+    CandidateResp, ExecMsg, GenericProposalResp, InstantiateMsg, JoiningFeeResp, ProposerResp,
+    QueryMsg, StatusResp, TallyResp, ThresholdResp, TotalYesTokensResp, VoteOption, VotersResp,
+};
+use crate::{execute, instantiate, migrate, query, reply};
 
 #[cfg(test)]
 mod tests;
@@ -15,9 +19,21 @@ pub struct CodeId(u64);
 
 impl CodeId {
     pub fn store_code(app: &mut App) -> Self {
-        let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+        let contract = ContractWrapper::new_with_empty(execute, instantiate, query)
+            .with_reply_empty(reply)
+            .with_migrate_empty(migrate);
         CodeId(app.store_code(Box::new(contract)))
     }
+
+    #[track_caller]
+    pub fn instantiate(
+        self,
+        app: &mut App,
+        sender: &Addr,
+        msg: &InstantiateMsg,
+    ) -> AnyResult<Contract> {
+        Contract::instantiate(app, self, sender, msg)
+    }
 }
 
 impl From<CodeId> for u64 {
@@ -38,6 +54,24 @@ impl Contract {
         &self.0
     }
 
+    #[track_caller]
+    pub fn instantiate(
+        app: &mut App,
+        code_id: CodeId,
+        sender: &Addr,
+        msg: &InstantiateMsg,
+    ) -> AnyResult<Self> {
+        app.instantiate_contract(code_id.0, sender.clone(), msg, &[], "Proposal", None)
+            .map(Self)
+    }
+
+    #[track_caller]
+    pub fn pass(&self, app: &mut App, sender: &Addr) -> AnyResult<()> {
+        let msg = ExecMsg::Pass {};
+        app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])?;
+        Ok(())
+    }
+
     #[track_caller]
     pub fn join(
         &self,
@@ -46,20 +80,115 @@ impl Contract {
         funds: &[Coin],
     ) -> AnyResult<Option<ProxyMemberData>> {
         let msg = ExecMsg::Join {};
-        let resp = app.execute_contract(sender.clone(), self.addr().clone(), &msg, &funds)?;
+        let resp = app.execute_contract(sender.clone(), self.addr().clone(), &msg, funds)?;
         resp.data
             .map(|data| parse_execute_response_data(&data))
             .transpose()?
             .and_then(|data| data.data)
-            .map(|data| from_binary(&data))
+            .map(|data| from_json(&data))
             .transpose()
             .map_err(Into::into)
     }
 
     #[track_caller]
-    pub fn vote(&self, app: &mut App, sender: &Addr, funds: &[Coin]) -> AnyResult<()> {
-        let msg = ExecMsg::Vote {};
+    pub fn vote(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        option: VoteOption,
+        funds: &[Coin],
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::Vote { option };
         app.execute_contract(sender.clone(), self.addr().clone(), &msg, funds)?;
         Ok(())
     }
+
+    #[track_caller]
+    pub fn retract(&self, app: &mut App, sender: &Addr, amount: u128) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::Retract {
+            amount: Uint128::new(amount),
+        };
+        app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn close(&self, app: &mut App, sender: &Addr) -> AnyResult<()> {
+        let msg = ExecMsg::Close {};
+        app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])?;
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn status(&self, app: &App) -> AnyResult<StatusResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Status {})
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn candidate(&self, app: &App) -> AnyResult<CandidateResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Candidate {})
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn proposer(&self, app: &App) -> AnyResult<ProposerResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Proposer {})
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn joining_fee(&self, app: &App) -> AnyResult<JoiningFeeResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::JoiningFee {})
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn generic_proposal(&self, app: &App) -> AnyResult<GenericProposalResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::GenericProposal {})
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn total_yes_tokens(&self, app: &App) -> AnyResult<TotalYesTokensResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::TotalYesTokens {})
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn tally(&self, app: &App) -> AnyResult<TallyResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Tally {})
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn threshold(&self, app: &App) -> AnyResult<ThresholdResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Threshold {})
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn voters(
+        &self,
+        app: &App,
+        option: VoteOption,
+        start_after: Option<&Addr>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<VotersResp> {
+        let msg = QueryMsg::Voters {
+            option,
+            start_after: start_after.map(|addr| addr.to_string()),
+            limit: limit.into(),
+        };
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &msg)
+            .map_err(Into::into)
+    }
 }
@@ -1 +1,590 @@
+use common::keys::{ATOM, VOTE_DENOM};
+use common::msg::membership::{
+    ExecMsg as MembershipExecMsg, IsMemberResp, OwnerProxyResp, QueryMsg as MembershipQueryMsg,
+};
+use common::multitest::{App, TokenFactory};
+use common::vote_token::VoteAsset;
+use cosmwasm_std::{
+    coin, coins, to_json_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128,
+};
+use cw_multi_test::{next_block, ContractWrapper, Executor};
+use cw_utils::Expiration;
+use distribution::msg::{
+    ExecMsg as DistributionExecMsg, QueryMsg as DistributionQueryMsg, VotingPowerResp,
+};
 
+use super::{CodeId, Contract};
+use crate::error::ContractError;
+use crate::msg::{InstantiateMsg, ProposalKind, ProposalStatus, VoteOption, VoterResp};
+
+const VOTING_POWER: u128 = 10;
+const TOTAL_VOTING_POWER: u128 = 30;
+const VOTING_PERIOD: u64 = 100;
+
+/// Membership standing in for the real one: every proxy is a member proxy owned by itself and
+/// every candidate is proposed
+fn membership_contract(app: &mut App) -> Addr {
+    let instantiate = |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+        Ok(Response::new())
+    };
+    let execute =
+        |_: DepsMut, _: Env, _: MessageInfo, _: MembershipExecMsg| -> StdResult<Response> {
+            Ok(Response::new())
+        };
+    let query = |_: Deps, _: Env, msg: MembershipQueryMsg| -> StdResult<Binary> {
+        match msg {
+            MembershipQueryMsg::MemberByProxy { proxy } => to_json_binary(&OwnerProxyResp {
+                owner: proxy.clone(),
+                proxy,
+            }),
+            MembershipQueryMsg::IsMember { .. } | MembershipQueryMsg::IsProposedMember { .. } => {
+                to_json_binary(&IsMemberResp { ok: true })
+            }
+            _ => Err(StdError::generic_err("not supported by the mock")),
+        }
+    };
+
+    let code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        execute,
+        instantiate,
+        query,
+    )));
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked("admin"),
+        &Empty {},
+        &[],
+        "Membership",
+        None,
+    )
+    .unwrap()
+}
+
+/// Distribution standing in for the real one: every member has the same voting power
+fn distribution_contract(app: &mut App) -> Addr {
+    let instantiate = |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+        Ok(Response::new())
+    };
+    let execute =
+        |_: DepsMut, _: Env, _: MessageInfo, _: DistributionExecMsg| -> StdResult<Response> {
+            Ok(Response::new())
+        };
+    let query = |_: Deps, env: Env, msg: DistributionQueryMsg| -> StdResult<Binary> {
+        let power = match msg {
+            DistributionQueryMsg::VotingPower { .. } => VOTING_POWER,
+            DistributionQueryMsg::TotalVotingPower { .. } => TOTAL_VOTING_POWER,
+            _ => return Err(StdError::generic_err("not supported by the mock")),
+        };
+        to_json_binary(&VotingPowerResp {
+            power: Uint128::new(power),
+            height: env.block.height,
+        })
+    };
+
+    let code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        execute,
+        instantiate,
+        query,
+    )));
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked("admin"),
+        &Empty {},
+        &[],
+        "Distribution",
+        None,
+    )
+    .unwrap()
+}
+
+struct Suite {
+    app: App,
+    membership: Addr,
+    distribution: Addr,
+}
+
+impl Suite {
+    /// alice, bob and charlie are member proxies holding vote tokens, dave is a candidate holding
+    /// fees in several denoms
+    fn new() -> Self {
+        let mut balances: Vec<_> = ["alice", "bob", "charlie"]
+            .into_iter()
+            .map(|voter| (Addr::unchecked(voter), coins(20, VOTE_DENOM)))
+            .collect();
+        balances.push((
+            Addr::unchecked("dave"),
+            vec![coin(60, ATOM), coin(30, "ujuno"), coin(30, "uosmo")],
+        ));
+        let mut app = TokenFactory::app_with_balances(balances);
+        let membership = membership_contract(&mut app);
+        let distribution = distribution_contract(&mut app);
+        app.update_block(next_block);
+
+        Self {
+            app,
+            membership,
+            distribution,
+        }
+    }
+
+    /// Proposal made by alice escrowing 5 vote tokens, as membership does it
+    #[track_caller]
+    fn propose(&mut self, kind: ProposalKind) -> Contract {
+        let alice = Addr::unchecked("alice");
+        let msg = InstantiateMsg {
+            proposer: alice.to_string(),
+            distribution_contract: self.distribution.to_string(),
+            membership_contract: self.membership.to_string(),
+            kind,
+            expiration: Expiration::AtHeight(self.app.block_info().height + VOTING_PERIOD),
+            quorum: Decimal::percent(50),
+            threshold: Decimal::percent(50),
+            vote_token: VoteAsset::Native(VOTE_DENOM.to_owned()),
+            escrow: Uint128::new(5),
+        };
+
+        let proposal_id = CodeId::store_code(&mut self.app);
+        let proposal = proposal_id
+            .instantiate(&mut self.app, &self.membership, &msg)
+            .unwrap();
+        self.app
+            .send_tokens(alice, proposal.addr().clone(), &coins(5, VOTE_DENOM))
+            .unwrap();
+        proposal
+    }
+
+    #[track_caller]
+    fn propose_member(&mut self) -> Contract {
+        self.propose(ProposalKind::Member {
+            proposed_owner: "dave".to_owned(),
+            joining_fee: coins(30, ATOM),
+        })
+    }
+
+    fn vote_balance(&self, addr: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, VOTE_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    fn expire(&mut self) {
+        self.app.update_block(|block| block.height += VOTING_PERIOD);
+    }
+}
+
+#[test]
+fn member_proposal_queries() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = Suite::new();
+    let proposal = suite.propose_member();
+
+    assert_eq!(
+        proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Open
+    );
+    assert_eq!(
+        proposal.status(&suite.app).unwrap().start_height,
+        suite.app.block_info().height
+    );
+    assert_eq!(proposal.candidate(&suite.app).unwrap().candidate, "dave");
+    assert_eq!(proposal.proposer(&suite.app).unwrap().proposer, "alice");
+    assert_eq!(
+        proposal.joining_fee(&suite.app).unwrap().fee,
+        coins(30, ATOM)
+    );
+    assert!(proposal.generic_proposal(&suite.app).is_err());
+
+    let threshold = proposal.threshold(&suite.app).unwrap();
+    assert_eq!(
+        threshold.total_vote_tokens_in_circulation,
+        coin(TOTAL_VOTING_POWER, VOTE_DENOM)
+    );
+    assert_eq!(threshold.quorum, Decimal::percent(50));
+    assert_eq!(threshold.threshold, Decimal::percent(50));
+
+    proposal
+        .vote(&mut suite.app, &bob, VoteOption::Yes, &coins(3, VOTE_DENOM))
+        .unwrap();
+    proposal
+        .vote(&mut suite.app, &bob, VoteOption::Yes, &coins(2, VOTE_DENOM))
+        .unwrap();
+
+    assert_eq!(
+        proposal.total_yes_tokens(&suite.app).unwrap().tokens,
+        coin(10, VOTE_DENOM)
+    );
+
+    let voters = [
+        VoterResp {
+            voter: alice.to_string(),
+            option: VoteOption::Yes,
+            tokens: coin(5, VOTE_DENOM),
+        },
+        VoterResp {
+            voter: bob.to_string(),
+            option: VoteOption::Yes,
+            tokens: coin(5, VOTE_DENOM),
+        },
+    ];
+
+    let first_page = proposal
+        .voters(&suite.app, VoteOption::Yes, None, 1)
+        .unwrap()
+        .voters;
+    assert_eq!(first_page, voters[..1]);
+
+    let second_page = proposal
+        .voters(&suite.app, VoteOption::Yes, Some(&alice), None)
+        .unwrap()
+        .voters;
+    assert_eq!(second_page, voters[1..]);
+    assert!(proposal
+        .voters(&suite.app, VoteOption::No, None, None)
+        .unwrap()
+        .voters
+        .is_empty());
+
+    // only membership passes proposals
+    let err = proposal.pass(&mut suite.app, &alice).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::Unauthorized
+    ));
+
+    proposal.pass(&mut suite.app, &suite.membership).unwrap();
+    assert_eq!(
+        proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
+    );
+
+    proposal
+        .join(&mut suite.app, &dave, &coins(30, ATOM))
+        .unwrap();
+    assert_eq!(
+        proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Joined
+    );
+
+    // the escrow goes to membership, the fee to distribution
+    assert_eq!(suite.vote_balance(proposal.addr().as_str()), 0);
+    assert_eq!(suite.vote_balance(suite.membership.as_str()), 10);
+    assert_eq!(
+        suite
+            .app
+            .wrap()
+            .query_balance(&suite.distribution, ATOM)
+            .unwrap(),
+        coin(30, ATOM)
+    );
+}
+
+#[test]
+fn generic_proposal_queries() {
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = Suite::new();
+    let grant = BankMsg::Send {
+        to_address: dave.to_string(),
+        amount: coins(20, ATOM),
+    };
+    let proposal = suite.propose(ProposalKind::Generic {
+        title: "Grant".to_owned(),
+        description: "Fund the grantee".to_owned(),
+        msgs: vec![grant.clone().into()],
+    });
+
+    let generic = proposal.generic_proposal(&suite.app).unwrap();
+    assert_eq!(generic.title, "Grant");
+    assert_eq!(generic.description, "Fund the grantee");
+    assert_eq!(generic.msgs, vec![grant.into()]);
+    assert!(proposal.candidate(&suite.app).is_err());
+    assert!(proposal.joining_fee(&suite.app).is_err());
+
+    let err = proposal.join(&mut suite.app, &dave, &[]).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::NotMemberProposal
+    ));
+
+    // escrow of a passed generic proposal goes back to distribution right away
+    proposal.pass(&mut suite.app, &suite.membership).unwrap();
+    assert_eq!(suite.vote_balance(proposal.addr().as_str()), 0);
+    assert_eq!(suite.vote_balance(suite.distribution.as_str()), 5);
+}
+
+#[test]
+fn votes_rejected() {
+    let bob = Addr::unchecked("bob");
+    let charlie = Addr::unchecked("charlie");
+
+    let mut suite = Suite::new();
+    let proposal = suite.propose_member();
+
+    // a voter sticks to a single option
+    proposal
+        .vote(&mut suite.app, &bob, VoteOption::No, &coins(1, VOTE_DENOM))
+        .unwrap();
+    let err = proposal
+        .vote(&mut suite.app, &bob, VoteOption::Yes, &coins(1, VOTE_DENOM))
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::VoteRejectedOptionMismatch { option } if option == "no"
+    ));
+
+    // votes add up to no more than the voting power at the start height
+    let err = proposal
+        .vote(
+            &mut suite.app,
+            &bob,
+            VoteOption::No,
+            &coins(VOTING_POWER, VOTE_DENOM),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::VoteRejectedInsufficientPower { power } if power == Uint128::new(VOTING_POWER)
+    ));
+    let err = proposal
+        .vote(
+            &mut suite.app,
+            &charlie,
+            VoteOption::Yes,
+            &coins(VOTING_POWER + 1, VOTE_DENOM),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::VoteRejectedInsufficientPower { power } if power == Uint128::new(VOTING_POWER)
+    ));
+
+    proposal.pass(&mut suite.app, &suite.membership).unwrap();
+    let err = proposal
+        .vote(
+            &mut suite.app,
+            &charlie,
+            VoteOption::Yes,
+            &coins(1, VOTE_DENOM),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::VoteRejectedProposalWasPassedEarlier
+    ));
+
+    let proposal = suite.propose_member();
+    suite.expire();
+    let err = proposal
+        .vote(
+            &mut suite.app,
+            &charlie,
+            VoteOption::Yes,
+            &coins(1, VOTE_DENOM),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::VoteRejectedProposalExpired
+    ));
+}
+
+#[test]
+fn joins_rejected() {
+    let bob = Addr::unchecked("bob");
+    let dave = Addr::unchecked("dave");
+
+    let mut suite = Suite::new();
+    let proposal = suite.propose(ProposalKind::Member {
+        proposed_owner: dave.to_string(),
+        joining_fee: vec![coin(30, ATOM), coin(24, "uosmo")],
+    });
+
+    // neither a denom out of the list nor less than the fee in a listed one
+    for fee in [coin(30, "ujuno"), coin(20, "uosmo")] {
+        let err = proposal.join(&mut suite.app, &dave, &[fee]).unwrap_err();
+        assert!(matches!(
+            err.downcast().unwrap(),
+            ContractError::JoinRejected { fee } if fee == [coin(30, ATOM), coin(24, "uosmo")]
+        ));
+    }
+
+    let err = proposal
+        .join(&mut suite.app, &dave, &coins(30, ATOM))
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::JoinRejectedProposalNotPassed
+    ));
+
+    proposal.pass(&mut suite.app, &suite.membership).unwrap();
+
+    // only the candidate joins
+    suite
+        .app
+        .send_tokens(dave.clone(), bob.clone(), &coins(30, ATOM))
+        .unwrap();
+    let err = proposal
+        .join(&mut suite.app, &bob, &coins(30, ATOM))
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::Unauthorized
+    ));
+
+    suite.expire();
+    let err = proposal
+        .join(&mut suite.app, &dave, &coins(30, ATOM))
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::JoinRejectedProposalExpired
+    ));
+}
+
+#[test]
+fn passed_member_proposal_expires_unless_joined() {
+    let mut suite = Suite::new();
+    let member_proposal = suite.propose_member();
+    let generic_proposal = suite.propose(ProposalKind::Generic {
+        title: "Nothing".to_owned(),
+        description: "Does nothing".to_owned(),
+        msgs: vec![],
+    });
+
+    member_proposal
+        .pass(&mut suite.app, &suite.membership)
+        .unwrap();
+    generic_proposal
+        .pass(&mut suite.app, &suite.membership)
+        .unwrap();
+    for proposal in [&member_proposal, &generic_proposal] {
+        assert_eq!(
+            proposal.status(&suite.app).unwrap().status,
+            ProposalStatus::Passed
+        );
+    }
+
+    // the candidate can no longer join, the generic proposal was executed as it passed
+    suite.expire();
+    assert_eq!(
+        member_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Expired
+    );
+    assert_eq!(
+        generic_proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Passed
+    );
+}
+
+#[test]
+fn expired_proposal_closed_refunds_voters() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+
+    let mut suite = Suite::new();
+    let proposal = suite.propose_member();
+    proposal
+        .vote(&mut suite.app, &bob, VoteOption::No, &coins(3, VOTE_DENOM))
+        .unwrap();
+
+    let err = proposal.close(&mut suite.app, &bob).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::CloseRejectedProposalNotExpired
+    ));
+
+    suite.expire();
+    assert_eq!(
+        proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Expired
+    );
+
+    proposal.close(&mut suite.app, &bob).unwrap();
+    assert_eq!(
+        proposal.status(&suite.app).unwrap().status,
+        ProposalStatus::Closed
+    );
+    assert_eq!(suite.vote_balance(proposal.addr().as_str()), 0);
+    assert_eq!(suite.vote_balance(alice.as_str()), 20);
+    assert_eq!(suite.vote_balance(bob.as_str()), 20);
+
+    let err = proposal.close(&mut suite.app, &bob).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::ProposalAlreadyClosed
+    ));
+
+    // a passed generic proposal released its escrow already, there is nothing to close
+    let proposal = suite.propose(ProposalKind::Generic {
+        title: "Nothing".to_owned(),
+        description: "Does nothing".to_owned(),
+        msgs: vec![],
+    });
+    proposal.pass(&mut suite.app, &suite.membership).unwrap();
+    suite.expire();
+    let err = proposal.close(&mut suite.app, &bob).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::CloseRejectedProposalWasPassed
+    ));
+}
+
+#[test]
+fn votes_retracted_while_the_proposal_is_open() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let charlie = Addr::unchecked("charlie");
+
+    let mut suite = Suite::new();
+    let proposal = suite.propose_member();
+
+    proposal
+        .vote(&mut suite.app, &bob, VoteOption::Yes, &coins(4, VOTE_DENOM))
+        .unwrap();
+    let resp = proposal.retract(&mut suite.app, &bob, 2).unwrap();
+    assert!(resp.events.iter().any(|event| event.ty == "wasm"
+        && event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "action" && attr.value == "retract_vote")
+        && event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "remaining" && attr.value == "2")));
+
+    assert_eq!(proposal.tally(&suite.app).unwrap().yes, Uint128::new(7));
+    assert_eq!(suite.vote_balance(bob.as_str()), 18);
+
+    let err = proposal.retract(&mut suite.app, &bob, 3).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::RetractRejectedExceedsVote { voted } if voted == Uint128::new(2)
+    ));
+    let err = proposal.retract(&mut suite.app, &charlie, 1).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::RetractRejectedExceedsVote { voted } if voted.is_zero()
+    ));
+
+    proposal.pass(&mut suite.app, &suite.membership).unwrap();
+    let err = proposal.retract(&mut suite.app, &alice, 1).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::RetractRejectedProposalWasPassedEarlier
+    ));
+
+    let proposal = suite.propose_member();
+    suite.expire();
+    let err = proposal.retract(&mut suite.app, &alice, 1).unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::RetractRejectedProposalExpired
+    ));
+}
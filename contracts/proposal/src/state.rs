@@ -1,16 +1,93 @@
+use common::vote_token::VoteAsset;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, StdResult, Uint128};
 use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+
+use crate::msg::VoteOption;
 
 #[cw_serde]
 pub struct Config {
     pub distribution_contract: Addr,
     pub membership_contract: Addr,
-    pub joining_fee: Coin,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub vote_token: VoteAsset,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Tally {
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+}
+
+impl Tally {
+    pub fn add(&mut self, option: VoteOption, amount: Uint128) {
+        match option {
+            VoteOption::Yes => self.yes += amount,
+            VoteOption::No => self.no += amount,
+            VoteOption::Abstain => self.abstain += amount,
+        }
+    }
+
+    pub fn sub(&mut self, option: VoteOption, amount: Uint128) -> StdResult<()> {
+        match option {
+            VoteOption::Yes => self.yes = self.yes.checked_sub(amount)?,
+            VoteOption::No => self.no = self.no.checked_sub(amount)?,
+            VoteOption::Abstain => self.abstain = self.abstain.checked_sub(amount)?,
+        }
+        Ok(())
+    }
+
+    pub fn total(&self) -> Uint128 {
+        self.yes + self.no + self.abstain
+    }
+
+    /// Turnout reaches the quorum and yes votes are more than the threshold of yes and no votes
+    pub fn is_passed(
+        &self,
+        total_vote_tokens: Uint128,
+        quorum: Decimal,
+        threshold: Decimal,
+    ) -> bool {
+        if total_vote_tokens.is_zero() || (self.yes + self.no).is_zero() {
+            return false;
+        }
+
+        Decimal::from_ratio(self.total(), total_vote_tokens) >= quorum
+            && Decimal::from_ratio(self.yes, self.yes + self.no) > threshold
+    }
+}
+
+#[cw_serde]
+pub enum ProposalKind {
+    Member {
+        joining_fee: Vec<Coin>,
+    },
+    Generic {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg>,
+    },
+    Removal {
+        member: Addr,
+    },
 }
 
+// member proposals only
 pub const OWNER: Item<Addr> = Item::new("owner");
+pub const PROPOSER: Item<Addr> = Item::new("proposer");
 pub const CONFIG: Item<Config> = Item::new("config");
+pub const KIND: Item<ProposalKind> = Item::new("kind");
 pub const IS_PASSED: Item<bool> = Item::new("is_passed");
+pub const IS_JOINED: Item<bool> = Item::new("is_joined");
+pub const IS_CLOSED: Item<bool> = Item::new("is_closed");
+pub const EXPIRATION: Item<Expiration> = Item::new("expiration");
+pub const TALLY: Item<Tally> = Item::new("tally");
+// votes are counted against the voting power at the beginning of this height
+pub const START_HEIGHT: Item<u64> = Item::new("start_height");
 
-pub const VOTER_TOKENS: Map<&Addr, Coin> = Map::new("voter_tokens");
+// (vote-option, voter-proxy) => vote tokens
+pub const VOTER_TOKENS: Map<(&str, &Addr), Coin> = Map::new("voter_tokens");
@@ -1,28 +1,30 @@
-use common::keys::VOTE_DENOM;
 use cosmwasm_std::{
-    ensure, to_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply,
-    Response, StdResult,
+    ensure, from_json, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Reply, Response, StdResult,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
 
 mod exec;
 mod query;
 mod reply;
 
 use crate::error::ContractError;
-use crate::msg::{ExecMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{ExecMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::state::{Config, CONFIG, OWNER};
 
 const PROPOSE_MEMBER_REPLY_ID: u64 = 1;
 const WITHDRAW_REPLY_ID: u64 = 2;
 const BUY_VOTE_TOKENS_REPLY_ID: u64 = 3;
+const PROPOSE_GENERIC_REPLY_ID: u64 = 4;
+const PROPOSE_REMOVAL_REPLY_ID: u64 = 5;
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn instantiate(
     deps: DepsMut,
-    env: Env,
+    _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -36,24 +38,44 @@ pub fn instantiate(
 
     OWNER.save(deps.storage, &owner)?;
 
-    CONFIG.save(
-        deps.storage,
-        &Config {
-            distribution_contract,
-            membership_contract,
-        },
-    )?;
-
-    // move vote_tokens to actual owner
-    let vote_coins = deps
-        .querier
-        .query_balance(env.contract.address, VOTE_DENOM)?;
-    let bank_msg = BankMsg::Send {
-        to_address: owner.into_string(),
-        amount: vec![vote_coins],
+    let config = Config {
+        distribution_contract,
+        membership_contract,
+        vote_token: msg.vote_token,
     };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new())
+}
+
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    ensure!(
+        stored.contract == CONTRACT_NAME,
+        ContractError::MigrationInvalidContract {
+            name: stored.contract
+        }
+    );
+
+    let stored_version: Version = stored.version.parse()?;
+    let version: Version = CONTRACT_VERSION.parse()?;
+
+    ensure!(
+        stored_version <= version,
+        ContractError::MigrationDowngrade {
+            version: stored.version
+        }
+    );
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION);
 
-    Ok(Response::new().add_message(bank_msg))
+    Ok(resp)
 }
 
 pub fn execute(
@@ -65,9 +87,36 @@ pub fn execute(
     use ExecMsg::*;
 
     match msg {
-        ProposeMember { addr } => exec::propose_member(deps, info, addr),
-        BuyVoteTokens {} => exec::buy_vote_tokens(deps, env, info),
-        Withdraw {} => exec::withdraw(deps, env, info),
+        Receive(wrapper) => {
+            let config = CONFIG.load(deps.storage)?;
+            let (info, msg) = config
+                .vote_token
+                .receive(&info, wrapper)
+                .ok_or(ContractError::Unauthorized)?;
+            execute(deps, env, info, from_json(msg)?)
+        }
+        ProposeMember { addr, amount } => exec::propose_member(deps, env, info, addr, amount),
+        ProposeGeneric {
+            title,
+            description,
+            msgs,
+            amount,
+        } => exec::propose_generic(deps, env, info, title, description, msgs, amount),
+        ProposeRemoval { owner, amount } => exec::propose_removal(deps, env, info, owner, amount),
+        Vote {
+            proposal,
+            option,
+            amount,
+        } => exec::vote(deps, env, info, proposal, option, amount),
+        Retract { proposal, amount } => exec::retract(deps, info, proposal, amount),
+        Delegate { to, amount } => exec::delegate(deps, env, info, to, amount),
+        Undelegate { from, amount } => exec::undelegate(deps, info, from, amount),
+        ReturnDelegation { recipient, amount } => {
+            exec::return_delegation(deps, env, info, recipient, amount)
+        }
+        Leave {} => exec::leave(deps, env, info),
+        BuyVoteTokens { amount } => exec::buy_vote_tokens(deps, env, info, amount),
+        Withdraw { amounts } => exec::withdraw(deps, env, info, amounts),
     }
 }
 
@@ -75,15 +124,17 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     use QueryMsg::*;
 
     match msg {
-        Withdrawable {} => to_binary(&query::withdrawable(deps, env)?),
+        Withdrawable {} => to_json_binary(&query::withdrawable(deps, env)?),
     }
 }
 
 pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id {
-        PROPOSE_MEMBER_REPLY_ID => reply::propose_member(reply.result.into_result()),
+        PROPOSE_MEMBER_REPLY_ID | PROPOSE_GENERIC_REPLY_ID | PROPOSE_REMOVAL_REPLY_ID => {
+            reply::forward_data(reply.result.into_result())
+        }
         WITHDRAW_REPLY_ID => reply::withdraw(deps, env, reply.result.into_result()),
-        BUY_VOTE_TOKENS_REPLY_ID => reply::buy_vote_tokens(deps, env, reply.result.into_result()),
+        BUY_VOTE_TOKENS_REPLY_ID => reply::buy_vote_tokens(reply.result.into_result()),
         id => Err(ContractError::UnrecognizedReplyId(id)),
     }
 }
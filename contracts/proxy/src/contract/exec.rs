@@ -1,34 +1,36 @@
-use common::keys::VOTE_DENOM;
 use common::msg::membership::ExecMsg as MembershipExecMsg;
 use cosmwasm_std::{
-    coins, ensure, to_binary, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg, Uint128,
-    WasmMsg,
+    ensure, to_json_binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    SubMsg, Uint128, WasmMsg,
 };
-use cw_utils::must_pay;
 use distribution::msg::ExecMsg as DistribtionExecMsg;
+use proposal::msg::{ExecMsg as ProposalExecMsg, VoteOption};
 
-use crate::contract::{BUY_VOTE_TOKENS_REPLY_ID, PROPOSE_MEMBER_REPLY_ID, WITHDRAW_REPLY_ID};
+use crate::contract::{
+    BUY_VOTE_TOKENS_REPLY_ID, PROPOSE_GENERIC_REPLY_ID, PROPOSE_MEMBER_REPLY_ID,
+    PROPOSE_REMOVAL_REPLY_ID, WITHDRAW_REPLY_ID,
+};
 use crate::error::ContractError;
-use crate::state::{CONFIG, OWNER};
+use crate::state::{Config, CONFIG, OWNER};
 
 pub fn propose_member(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     addr: String,
+    amount: Uint128,
 ) -> Result<Response, ContractError> {
-    let vote_tokens = must_pay(&info, VOTE_DENOM)?;
+    let config = CONFIG.load(deps.storage)?;
 
     let owner = OWNER.load(deps.storage)?;
     ensure!(owner == info.sender, ContractError::Unauthorized);
 
-    let config = CONFIG.load(deps.storage)?;
+    ensure_held(deps.as_ref(), &env, &config, amount)?;
 
     let propose_msg = MembershipExecMsg::ProposeMember { addr: addr.clone() };
-    let propose_msg = WasmMsg::Execute {
-        contract_addr: config.membership_contract.into_string(),
-        msg: to_binary(&propose_msg)?,
-        funds: coins(vote_tokens.u128(), VOTE_DENOM),
-    };
+    let propose_msg = config
+        .vote_token
+        .send(config.membership_contract, amount, &propose_msg)?;
 
     let propose_msg = SubMsg::reply_on_success(propose_msg, PROPOSE_MEMBER_REPLY_ID);
 
@@ -41,16 +43,290 @@ pub fn propose_member(
     Ok(resp)
 }
 
-pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn propose_generic(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    msgs: Vec<CosmosMsg>,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let owner = OWNER.load(deps.storage)?;
+    ensure!(owner == info.sender, ContractError::Unauthorized);
+
+    ensure_held(deps.as_ref(), &env, &config, amount)?;
+
+    let propose_msg = MembershipExecMsg::ProposeGeneric {
+        title: title.clone(),
+        description,
+        msgs,
+    };
+    let propose_msg = config
+        .vote_token
+        .send(config.membership_contract, amount, &propose_msg)?;
+
+    let propose_msg = SubMsg::reply_on_success(propose_msg, PROPOSE_GENERIC_REPLY_ID);
+
+    let resp = Response::new()
+        .add_submessage(propose_msg)
+        .add_attribute("action", "propose generic")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("title", title);
+
+    Ok(resp)
+}
+
+pub fn propose_removal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let proxy_owner = OWNER.load(deps.storage)?;
+    ensure!(proxy_owner == info.sender, ContractError::Unauthorized);
+
+    ensure_held(deps.as_ref(), &env, &config, amount)?;
+
+    let propose_msg = MembershipExecMsg::ProposeRemoval {
+        owner: owner.clone(),
+    };
+    let propose_msg = config
+        .vote_token
+        .send(config.membership_contract, amount, &propose_msg)?;
+
+    let propose_msg = SubMsg::reply_on_success(propose_msg, PROPOSE_REMOVAL_REPLY_ID);
+
+    let resp = Response::new()
+        .add_submessage(propose_msg)
+        .add_attribute("action", "propose removal")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("member", owner);
+
+    Ok(resp)
+}
+
+pub fn vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal: String,
+    option: VoteOption,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let owner = OWNER.load(deps.storage)?;
+    ensure!(owner == info.sender, ContractError::Unauthorized);
+
+    ensure_held(deps.as_ref(), &env, &config, amount)?;
+
+    let proposal = deps.api.addr_validate(&proposal)?;
+    let vote_msg = config
+        .vote_token
+        .send(&proposal, amount, &ProposalExecMsg::Vote { option })?;
+
+    let resp = Response::new()
+        .add_message(vote_msg)
+        .add_attribute("action", "vote")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("proposal", proposal.as_str())
+        .add_attribute("option", option.as_str());
+
+    Ok(resp)
+}
+
+pub fn retract(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let owner = OWNER.load(deps.storage)?;
+    ensure!(owner == info.sender, ContractError::Unauthorized);
+
+    let proposal = deps.api.addr_validate(&proposal)?;
+    let retract_msg = WasmMsg::Execute {
+        contract_addr: proposal.to_string(),
+        msg: to_json_binary(&ProposalExecMsg::Retract { amount })?,
+        funds: vec![],
+    };
+
+    let resp = Response::new()
+        .add_message(retract_msg)
+        .add_attribute("action", "retract")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("proposal", proposal.as_str());
+
+    Ok(resp)
+}
+
+pub fn delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let owner = OWNER.load(deps.storage)?;
+    ensure!(owner == info.sender, ContractError::Unauthorized);
+
+    ensure_held(deps.as_ref(), &env, &config, amount)?;
+
+    // membership passes the vote tokens on to the delegate proxy
+    let delegate_msg = MembershipExecMsg::Delegate { to: to.clone() };
+    let delegate_msg = config
+        .vote_token
+        .send(config.membership_contract, amount, &delegate_msg)?;
+
+    let resp = Response::new()
+        .add_message(delegate_msg)
+        .add_attribute("action", "delegate")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("to", to)
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+pub fn undelegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    from: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let owner = OWNER.load(deps.storage)?;
+    ensure!(owner == info.sender, ContractError::Unauthorized);
+
+    let undelegate_msg = MembershipExecMsg::Undelegate {
+        from: from.clone(),
+        amount,
+    };
+    let undelegate_msg = WasmMsg::Execute {
+        contract_addr: config.membership_contract.into_string(),
+        msg: to_json_binary(&undelegate_msg)?,
+        funds: vec![],
+    };
+
+    let resp = Response::new()
+        .add_message(undelegate_msg)
+        .add_attribute("action", "undelegate")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("from", from)
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+pub fn return_delegation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        config.membership_contract == info.sender,
+        ContractError::Unauthorized
+    );
+
+    ensure_held(deps.as_ref(), &env, &config, amount)?;
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let return_msg = config.vote_token.transfer(&recipient, amount)?;
+
+    let resp = Response::new()
+        .add_message(return_msg)
+        .add_attribute("action", "return_delegation")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("recipient", recipient.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    Ok(resp)
+}
+
+// proposals, votes and delegations are paid out of the vote tokens held by the proxy, including
+// any sent along by the owner or delegated to it
+fn ensure_held(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let balance = config
+        .vote_token
+        .balance(&deps.querier, &env.contract.address)?;
+    ensure!(
+        amount <= balance,
+        ContractError::InsufficientVoteTokens { balance }
+    );
+
+    Ok(())
+}
+
+pub fn leave(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let owner = OWNER.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        owner == info.sender || config.membership_contract == info.sender,
+        ContractError::Unauthorized
+    );
+
+    // unclaimed rewards are paid out to the owner while still a member
+    let withdraw_msg = DistribtionExecMsg::Withdraw { amounts: None };
+    let withdraw_msg = WasmMsg::Execute {
+        contract_addr: config.distribution_contract.into_string(),
+        msg: to_json_binary(&withdraw_msg)?,
+        funds: vec![],
+    };
+    let withdraw_msg = SubMsg::reply_on_success(withdraw_msg, WITHDRAW_REPLY_ID);
+
+    // everything the proxy holds goes back, including any vote tokens sent along by the owner
+    let vote_tokens = config
+        .vote_token
+        .balance(&deps.querier, env.contract.address)?;
+
+    let left_msg = config.vote_token.send(
+        config.membership_contract,
+        vote_tokens,
+        &MembershipExecMsg::MemberLeft {},
+    )?;
+
+    let resp = Response::new()
+        .add_submessage(withdraw_msg)
+        .add_message(left_msg)
+        .add_attribute("action", "leave")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("owner", owner.as_str());
+
+    Ok(resp)
+}
+
+pub fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amounts: Option<Vec<Coin>>,
+) -> Result<Response, ContractError> {
     let owner = OWNER.load(deps.storage)?;
     ensure!(owner == info.sender, ContractError::Unauthorized);
 
     let config = CONFIG.load(deps.storage)?;
 
-    let withdraw_msg = DistribtionExecMsg::Withdraw {};
+    let withdraw_msg = DistribtionExecMsg::Withdraw { amounts };
     let withdraw_msg = WasmMsg::Execute {
         contract_addr: config.distribution_contract.into_string(),
-        msg: to_binary(&withdraw_msg)?,
+        msg: to_json_binary(&withdraw_msg)?,
         funds: vec![],
     };
     let withdraw_msg = SubMsg::reply_on_success(withdraw_msg, WITHDRAW_REPLY_ID);
@@ -67,16 +343,17 @@ pub fn buy_vote_tokens(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    amount: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let owner = OWNER.load(deps.storage)?;
     ensure!(owner == info.sender, ContractError::Unauthorized);
 
     let config = CONFIG.load(deps.storage)?;
 
-    let msg = DistribtionExecMsg::BuyVoteTokens {};
+    let msg = DistribtionExecMsg::BuyVoteTokens { amount };
     let msg = WasmMsg::Execute {
         contract_addr: config.distribution_contract.into_string(),
-        msg: to_binary(&msg)?,
+        msg: to_json_binary(&msg)?,
         funds: vec![],
     };
     let msg = SubMsg::reply_on_success(msg, BUY_VOTE_TOKENS_REPLY_ID);
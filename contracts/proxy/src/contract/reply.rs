@@ -1,67 +1,67 @@
-use common::keys::{ATOM, VOTE_DENOM};
-use cosmwasm_std::{BankMsg, DepsMut, Env, Response, StdError, SubMsgResponse};
+use common::msg::BuyVoteTokensResp;
+use cosmwasm_std::{from_json, BankMsg, Coin, DepsMut, Env, Response, StdError, SubMsgResponse};
+use cw_utils::parse_execute_response_data;
 
-use crate::{
-    error::ContractError,
-    state::{CONFIG, OWNER},
-};
+use crate::{error::ContractError, state::OWNER};
 
-pub fn propose_member(reply: Result<SubMsgResponse, String>) -> Result<Response, ContractError> {
+/// Hands the proposal data membership returned back to the proxy owner, a cw20 `Send` returns
+/// none
+pub fn forward_data(reply: Result<SubMsgResponse, String>) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
+    let mut resp = Response::new();
     if let Some(data) = response.data {
-        let resp = Response::new().set_data(data);
-        Ok(resp)
-    } else {
-        Ok(Response::new())
+        resp = resp.set_data(data);
     }
+
+    Ok(resp)
 }
 
 pub fn withdraw(
     deps: DepsMut,
-    env: Env,
-    _reply: Result<SubMsgResponse, String>,
+    _env: Env,
+    reply: Result<SubMsgResponse, String>,
 ) -> Result<Response, ContractError> {
+    let response = reply.map_err(StdError::generic_err)?;
+    let data = response.data.ok_or(ContractError::DataMissing)?;
+    let response = parse_execute_response_data(&data)?;
+    // forward only what distribution paid out for this withdrawal
+    let rewards: Vec<Coin> = from_json(response.data.ok_or(ContractError::DataMissing)?)?;
+
     let owner = OWNER.load(deps.storage)?;
-    let rewards = deps.querier.query_balance(env.contract.address, ATOM)?;
 
-    if rewards.amount.u128() == 0 {
+    if rewards.is_empty() {
         return Ok(Response::new());
     }
 
+    let amount = rewards
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
     let bank_msg = BankMsg::Send {
         to_address: owner.into_string(),
-        amount: vec![rewards.clone()],
+        amount: rewards,
     };
 
     let resp = Response::new()
         .add_message(bank_msg)
-        .add_attribute("amount", rewards.to_string());
+        .add_attribute("amount", amount);
 
     Ok(resp)
 }
 
-pub fn buy_vote_tokens(
-    deps: DepsMut,
-    env: Env,
-    _reply: Result<SubMsgResponse, String>,
-) -> Result<Response, ContractError> {
-    let owner = OWNER.load(deps.storage)?;
-    let vote_tokens = deps
-        .querier
-        .query_balance(env.contract.address, VOTE_DENOM)?;
-
-    if vote_tokens.amount.u128() == 0 {
-        return Ok(Response::new());
-    }
-
-    let bank_msg = BankMsg::Send {
-        to_address: owner.into_string(),
-        amount: vec![vote_tokens.clone()],
-    };
+pub fn buy_vote_tokens(reply: Result<SubMsgResponse, String>) -> Result<Response, ContractError> {
+    let response = reply.map_err(StdError::generic_err)?;
+    let data = response.data.ok_or(ContractError::DataMissing)?;
+    let response = parse_execute_response_data(&data)?;
+    // bought vote tokens stay with the proxy, which votes with them
+    let bought: BuyVoteTokensResp = from_json(response.data.ok_or(ContractError::DataMissing)?)?;
 
     let resp = Response::new()
-        .add_message(bank_msg)
-        .add_attribute("amount", vote_tokens.to_string());
+        .add_attribute("amount", bought.vote_tokens.to_string())
+        .add_attribute("change", bought.change.to_string())
+        .set_data(data);
 
     Ok(resp)
 }
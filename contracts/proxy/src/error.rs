@@ -1,5 +1,5 @@
-use cosmwasm_std::StdError;
-use cw_utils::PaymentError;
+use cosmwasm_std::{StdError, Uint128};
+use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,9 +8,24 @@ pub enum ContractError {
     StdError(#[from] StdError),
     #[error("{0}")]
     PaymentError(#[from] PaymentError),
+    #[error("{0}")]
+    ParseError(#[from] ParseReplyError),
 
     #[error("Unauthorized")]
     Unauthorized,
     #[error("{0}")]
     UnrecognizedReplyId(u64),
+    #[error("Missing expected data")]
+    DataMissing,
+    #[error("Proxy holds only {balance} vote tokens")]
+    InsufficientVoteTokens { balance: Uint128 },
+
+    #[error("{0}")]
+    Semver(#[from] semver::Error),
+
+    #[error("Cannot migrate from contract {name}")]
+    MigrationInvalidContract { name: String },
+
+    #[error("Cannot migrate from newer version {version}")]
+    MigrationDowngrade { version: String },
 }
@@ -6,10 +6,10 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
 use error::ContractError;
-use msg::{ExecMsg, InstantiateMsg, QueryMsg};
+use msg::{ExecMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 mod contract;
-mod error;
+pub mod error;
 pub mod msg;
 pub mod state;
 
@@ -26,6 +26,11 @@ pub fn instantiate(
     contract::instantiate(deps, env, info, msg)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    contract::migrate(deps, env, msg)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
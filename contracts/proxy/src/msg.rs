@@ -1,20 +1,84 @@
-use common::msg::WithdrawableResp;
+use common::{msg::WithdrawableResp, vote_token::VoteAsset};
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Coin, CosmosMsg, Uint128};
+use cw20::Cw20ReceiveMsg;
+use proposal::msg::VoteOption;
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
     pub distribution_contract: String,
     pub membership_contract: String,
+    pub vote_token: VoteAsset,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecMsg {
-    ProposeMember { addr: String },
+    // cw20 vote tokens sent along with any of the messages
+    Receive(Cw20ReceiveMsg),
+
+    // proposals and votes draw `amount` from the vote tokens held by the proxy
+    ProposeMember {
+        addr: String,
+        amount: Uint128,
+    },
+
+    ProposeGeneric {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg>,
+        amount: Uint128,
+    },
+
+    ProposeRemoval {
+        owner: String,
+        amount: Uint128,
+    },
+
+    Vote {
+        proposal: String,
+        option: VoteOption,
+        amount: Uint128,
+    },
+
+    // retracted vote tokens come back to the proxy
+    Retract {
+        proposal: String,
+        amount: Uint128,
+    },
+
+    // lends `amount` of the vote tokens held by the proxy to the member `to`, whose proxy votes
+    // with them
+    Delegate {
+        to: String,
+        amount: Uint128,
+    },
+
+    // takes `amount` lent to the member `from` back
+    Undelegate {
+        from: String,
+        amount: Uint128,
+    },
+
+    // called by membership, hands delegated vote tokens back
+    ReturnDelegation {
+        recipient: String,
+        amount: Uint128,
+    },
+
+    // called by the owner, or by membership once a removal proposal passed
+    Leave {},
 
-    BuyVoteTokens {},
+    BuyVoteTokens {
+        amount: Option<Uint128>,
+    },
 
-    Withdraw {},
+    Withdraw {
+        amounts: Option<Vec<Coin>>,
+    },
 }
 
 #[cw_serde]
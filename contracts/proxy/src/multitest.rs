@@ -1,11 +1,16 @@
 use anyhow::{Ok, Result as AnyResult};
-use common::msg::{ProposalMemberData, ProxyMemberData, WithdrawableResp};
-use cosmwasm_std::{from_binary, Addr, Coin, Decimal};
-use cw_multi_test::{App, ContractWrapper, Executor};
+use common::msg::{
+    BuyVoteTokensResp, ProposalGenericData, ProposalMemberData, ProxyMemberData, WithdrawableResp,
+};
+use common::multitest::App;
+use common::vote_token::VoteAsset;
+use cosmwasm_std::{from_json, Addr, Coin, CosmosMsg, Decimal, Uint128};
+use cw_multi_test::{AppResponse, ContractWrapper, Executor};
 use cw_utils::parse_execute_response_data;
 
 use crate::msg::{ExecMsg, InstantiateMsg, QueryMsg};
-use crate::{execute, instantiate, query, reply};
+use crate::{execute, instantiate, migrate, query, reply};
+use proposal::msg::VoteOption;
 
 #[cfg(test)]
 mod tests;
@@ -15,31 +20,32 @@ pub struct CodeId(u64);
 
 impl CodeId {
     pub fn store_code(app: &mut App) -> Self {
-        let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+        let contract = ContractWrapper::new_with_empty(execute, instantiate, query)
+            .with_reply_empty(reply)
+            .with_migrate_empty(migrate);
         CodeId(app.store_code(Box::new(contract)))
     }
 
-    // #[allow(clippy::too_many_arguments)]
-    // #[track_caller]
-    // pub fn instantiate(
-    //     self,
-    //     app: &mut App,
-    //     sender: &str,
-    //     owner: &str,
-    //     distribution_contract: &str,
-    //     membership_contract: &str,
-    //     label: &str,
-    // ) -> AnyResult<Contract> {
-    //     Contract::instantiate(
-    //         app,
-    //         self,
-    //         sender,
-    //         owner,
-    //         distribution_contract,
-    //         membership_contract,
-    //         label,
-    //     )
-    // }
+    #[track_caller]
+    pub fn instantiate(
+        self,
+        app: &mut App,
+        sender: &Addr,
+        owner: &Addr,
+        membership_contract: &Addr,
+        distribution_contract: &Addr,
+        vote_token: VoteAsset,
+    ) -> AnyResult<Contract> {
+        Contract::instantiate(
+            app,
+            self,
+            sender,
+            owner,
+            membership_contract,
+            distribution_contract,
+            vote_token,
+        )
+    }
 }
 
 impl From<CodeId> for u64 {
@@ -60,62 +66,188 @@ impl Contract {
         &self.0
     }
 
-    // #[allow(clippy::too_many_arguments)]
-    // #[track_caller]
-    // pub fn instantiate(
-    //     app: &mut App,
-    //     code_id: CodeId,
-    //     sender: &str,
-    //     owner: &str,
-    //     distribution_contract: &str,
-    //     membership_contract: &str,
-    //     label: &str,
-    // ) -> AnyResult<Self> {
-    //     let ins_msg = InstantiateMsg {
-    //         distribution_contract: distribution_contract.to_owned(),
-    //         membership_contract: membership_contract.to_owned(),
-    //         owner: owner.to_owned(),
-    //     };
-
-    //     app.instantiate_contract(
-    //         code_id.0,
-    //         Addr::unchecked(sender),
-    //         &ins_msg,
-    //         &[],
-    //         label,
-    //         None,
-    //     )
-    //     .map(Self)
-    // }
+    #[track_caller]
+    pub fn instantiate(
+        app: &mut App,
+        code_id: CodeId,
+        sender: &Addr,
+        owner: &Addr,
+        membership_contract: &Addr,
+        distribution_contract: &Addr,
+        vote_token: VoteAsset,
+    ) -> AnyResult<Self> {
+        let msg = InstantiateMsg {
+            owner: owner.to_string(),
+            distribution_contract: distribution_contract.to_string(),
+            membership_contract: membership_contract.to_string(),
+            vote_token,
+        };
+
+        app.instantiate_contract(code_id.0, sender.clone(), &msg, &[], "Proxy", None)
+            .map(Self)
+    }
 
     #[track_caller]
     pub fn propose_member(
         &self,
         app: &mut App,
         sender: &Addr,
-        funds: &[Coin],
+        amount: u128,
         candidate: &Addr,
     ) -> AnyResult<Option<ProposalMemberData>> {
         let msg = ExecMsg::ProposeMember {
             addr: candidate.to_string(),
+            amount: Uint128::new(amount),
         };
-        let resp = app.execute_contract(sender.clone(), self.addr().clone(), &msg, funds)?;
+        let resp = app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])?;
         resp.data
             .map(|data| parse_execute_response_data(&data))
             .transpose()?
             .and_then(|data| data.data)
-            .map(|data| from_binary(&data))
+            .map(|data| from_json(&data))
             .transpose()
             .map_err(Into::into)
     }
 
     #[track_caller]
-    pub fn buy_vote_tokens<'a>(&self, app: &mut App, sender: &Addr) -> AnyResult<()> {
-        let msg = ExecMsg::BuyVoteTokens {};
-        app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])?;
+    pub fn propose_generic(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        amount: u128,
+        title: &str,
+        description: &str,
+        msgs: Vec<CosmosMsg>,
+    ) -> AnyResult<Option<ProposalGenericData>> {
+        let msg = ExecMsg::ProposeGeneric {
+            title: title.to_owned(),
+            description: description.to_owned(),
+            msgs,
+            amount: Uint128::new(amount),
+        };
+        let resp = app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])?;
+        resp.data
+            .map(|data| parse_execute_response_data(&data))
+            .transpose()?
+            .and_then(|data| data.data)
+            .map(|data| from_json(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn propose_removal(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        amount: u128,
+        member: &Addr,
+    ) -> AnyResult<Option<ProposalMemberData>> {
+        let msg = ExecMsg::ProposeRemoval {
+            owner: member.to_string(),
+            amount: Uint128::new(amount),
+        };
+        let resp = app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])?;
+        resp.data
+            .map(|data| parse_execute_response_data(&data))
+            .transpose()?
+            .and_then(|data| data.data)
+            .map(|data| from_json(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    #[track_caller]
+    pub fn vote(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        proposal: &Addr,
+        option: VoteOption,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::Vote {
+            proposal: proposal.to_string(),
+            option,
+            amount: Uint128::new(amount),
+        };
+        app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn retract(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        proposal: &Addr,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::Retract {
+            proposal: proposal.to_string(),
+            amount: Uint128::new(amount),
+        };
+        app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn delegate(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        to: &Addr,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::Delegate {
+            to: to.to_string(),
+            amount: Uint128::new(amount),
+        };
+        app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn undelegate(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        from: &Addr,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::Undelegate {
+            from: from.to_string(),
+            amount: Uint128::new(amount),
+        };
+        app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn leave(&self, app: &mut App, sender: &Addr, funds: &[Coin]) -> AnyResult<()> {
+        app.execute_contract(
+            sender.clone(),
+            self.addr().clone(),
+            &ExecMsg::Leave {},
+            funds,
+        )?;
         Ok(())
     }
 
+    #[track_caller]
+    pub fn buy_vote_tokens(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        amount: Option<Uint128>,
+    ) -> AnyResult<Option<BuyVoteTokensResp>> {
+        let msg = ExecMsg::BuyVoteTokens { amount };
+        let resp = app.execute_contract(sender.clone(), self.addr().clone(), &msg, &[])?;
+        resp.data
+            .map(|data| parse_execute_response_data(&data))
+            .transpose()?
+            .and_then(|data| data.data)
+            .map(|data| from_json(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
     #[track_caller]
     pub fn withdrawable(&self, app: &App) -> AnyResult<WithdrawableResp> {
         app.wrap()
@@ -124,11 +256,16 @@ impl Contract {
     }
 
     #[track_caller]
-    pub fn withdraw(&self, app: &mut App, sender: &Addr) -> AnyResult<()> {
+    pub fn withdraw(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        amounts: Option<Vec<Coin>>,
+    ) -> AnyResult<()> {
         app.execute_contract(
             Addr::unchecked(sender),
             self.0.clone(),
-            &ExecMsg::Withdraw {},
+            &ExecMsg::Withdraw { amounts },
             &[],
         )?;
         Ok(())
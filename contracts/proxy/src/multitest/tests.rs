@@ -1 +1,253 @@
+use common::keys::VOTE_DENOM;
+use common::multitest::{App, TokenFactory};
+use common::vote_token::VoteAsset;
+use cosmwasm_std::{
+    coin, coins, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw2::{set_contract_version, ContractVersion};
+use cw_multi_test::{ContractWrapper, Executor};
+use proposal::msg::VoteOption;
 
+use super::{CodeId, Contract};
+use crate::error::ContractError;
+use crate::msg::{ExecMsg, MigrateMsg};
+
+fn stored_version_contract(app: &mut App, contract: &str, version: &str) -> Addr {
+    let instantiate =
+        |deps: DepsMut, _: Env, _: MessageInfo, msg: ContractVersion| -> StdResult<Response> {
+            set_contract_version(deps.storage, msg.contract, msg.version)?;
+            Ok(Response::new())
+        };
+    let execute = |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+        Ok(Response::new())
+    };
+    let query = |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { Ok(Binary::default()) };
+
+    let code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        execute,
+        instantiate,
+        query,
+    )));
+    let admin = Addr::unchecked("admin");
+    let msg = ContractVersion {
+        contract: contract.to_owned(),
+        version: version.to_owned(),
+    };
+
+    app.instantiate_contract(
+        code_id,
+        admin.clone(),
+        &msg,
+        &[],
+        "Stored version",
+        Some(admin.to_string()),
+    )
+    .unwrap()
+}
+
+#[test]
+fn migrate_rejects_downgrade() {
+    let mut app = TokenFactory::app();
+    let admin = Addr::unchecked("admin");
+
+    let proxy_id = u64::from(CodeId::store_code(&mut app));
+    let contract = stored_version_contract(&mut app, "proxy", "99.0.0");
+
+    let err = app
+        .migrate_contract(admin, contract, &MigrateMsg {}, proxy_id)
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::MigrationDowngrade { version } if version == "99.0.0"
+    ));
+}
+
+#[test]
+fn migrate_rejects_other_contract() {
+    let mut app = TokenFactory::app();
+    let admin = Addr::unchecked("admin");
+
+    let proxy_id = u64::from(CodeId::store_code(&mut app));
+    let contract = stored_version_contract(&mut app, "proposal", "0.1.0");
+
+    let err = app
+        .migrate_contract(admin, contract, &MigrateMsg {}, proxy_id)
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::MigrationInvalidContract { name } if name == "proposal"
+    ));
+}
+
+#[test]
+fn migrate_updates_older_version() {
+    let mut app = TokenFactory::app();
+    let admin = Addr::unchecked("admin");
+
+    let proxy_id = u64::from(CodeId::store_code(&mut app));
+    let contract = stored_version_contract(&mut app, "proxy", "0.0.1");
+
+    app.migrate_contract(admin, contract.clone(), &MigrateMsg {}, proxy_id)
+        .unwrap();
+
+    let version = cw2::query_contract_info(&app.wrap(), contract).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+}
+
+/// Proxy of alice holding 10 vote tokens
+fn funded_proxy() -> (App, Contract) {
+    let alice = Addr::unchecked("alice");
+    let mut app = TokenFactory::app_with_balances([(alice.clone(), coins(10, VOTE_DENOM))]);
+
+    let proxy_id = CodeId::store_code(&mut app);
+    let proxy = proxy_id
+        .instantiate(
+            &mut app,
+            &Addr::unchecked("membership"),
+            &alice,
+            &Addr::unchecked("membership"),
+            &Addr::unchecked("distribution"),
+            VoteAsset::Native(VOTE_DENOM.to_owned()),
+        )
+        .unwrap();
+    app.send_tokens(alice, proxy.addr().clone(), &coins(10, VOTE_DENOM))
+        .unwrap();
+
+    (app, proxy)
+}
+
+#[test]
+fn only_the_owner_drives_the_proxy() {
+    let (mut app, proxy) = funded_proxy();
+    let bob = Addr::unchecked("bob");
+    let proposal = Addr::unchecked("proposal");
+
+    let errs = [
+        proxy
+            .propose_member(&mut app, &bob, 1, &bob)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .propose_generic(&mut app, &bob, 1, "Nothing", "Does nothing", vec![])
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .propose_removal(&mut app, &bob, 1, &bob)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .vote(&mut app, &bob, &proposal, VoteOption::Yes, 1)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .retract(&mut app, &bob, &proposal, 1)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .delegate(&mut app, &bob, &bob, 1)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .undelegate(&mut app, &bob, &bob, 1)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy.leave(&mut app, &bob, &[]).unwrap_err(),
+        proxy
+            .buy_vote_tokens(&mut app, &bob, None)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy.withdraw(&mut app, &bob, None).unwrap_err(),
+    ];
+
+    for err in errs {
+        assert!(matches!(
+            err.downcast().unwrap(),
+            ContractError::Unauthorized
+        ));
+    }
+}
+
+#[test]
+fn vote_tokens_drawn_from_the_proxy_balance() {
+    let (mut app, proxy) = funded_proxy();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let proposal = Addr::unchecked("proposal");
+
+    let errs = [
+        proxy
+            .propose_member(&mut app, &alice, 11, &bob)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .propose_generic(&mut app, &alice, 11, "Nothing", "Does nothing", vec![])
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .propose_removal(&mut app, &alice, 11, &bob)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .vote(&mut app, &alice, &proposal, VoteOption::Yes, 11)
+            .map(|_| ())
+            .unwrap_err(),
+        proxy
+            .delegate(&mut app, &alice, &bob, 11)
+            .map(|_| ())
+            .unwrap_err(),
+    ];
+
+    for err in errs {
+        assert!(matches!(
+            err.downcast().unwrap(),
+            ContractError::InsufficientVoteTokens { balance } if balance == Uint128::new(10)
+        ));
+    }
+}
+
+#[test]
+fn delegations_returned_on_behalf_of_membership_only() {
+    let (mut app, proxy) = funded_proxy();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let membership = Addr::unchecked("membership");
+
+    let return_delegation = |amount: u128| ExecMsg::ReturnDelegation {
+        recipient: bob.to_string(),
+        amount: Uint128::new(amount),
+    };
+
+    let err = app
+        .execute_contract(alice, proxy.addr().clone(), &return_delegation(4), &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::Unauthorized
+    ));
+
+    let err = app
+        .execute_contract(
+            membership.clone(),
+            proxy.addr().clone(),
+            &return_delegation(11),
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::InsufficientVoteTokens { balance } if balance == Uint128::new(10)
+    ));
+
+    app.execute_contract(membership, proxy.addr().clone(), &return_delegation(4), &[])
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_balance(&bob, VOTE_DENOM).unwrap(),
+        coin(4, VOTE_DENOM)
+    );
+    assert_eq!(
+        app.wrap().query_balance(proxy.addr(), VOTE_DENOM).unwrap(),
+        coin(6, VOTE_DENOM)
+    );
+}
@@ -1,3 +1,4 @@
+use common::vote_token::VoteAsset;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
 use cw_storage_plus::Item;
@@ -6,6 +7,7 @@ use cw_storage_plus::Item;
 pub struct Config {
     pub distribution_contract: Addr,
     pub membership_contract: Addr,
+    pub vote_token: VoteAsset,
 }
 
 pub const OWNER: Item<Addr> = Item::new("owner");